@@ -54,6 +54,22 @@ impl<F: Field> AllocatedBool<F> {
         })
     }
 
+    /// Wraps an existing variable without a booleanity check.
+    ///
+    /// The caller is responsible for ensuring that `variable` is constrained
+    /// to be zero or one.
+    pub(crate) fn new_variable_without_booleanity_check(
+        variable: Variable,
+        value: Option<bool>,
+        cs: ConstraintSystemRef<F>,
+    ) -> Self {
+        Self {
+            variable,
+            cs,
+            value,
+        }
+    }
+
     /// Performs an XOR operation over the two operands, returning
     /// an `AllocatedBool`.
     #[tracing::instrument(target = "gr1cs")]
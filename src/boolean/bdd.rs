@@ -0,0 +1,149 @@
+use ark_ff::PrimeField;
+use ark_relations::gr1cs::SynthesisError;
+use ark_std::marker::PhantomData;
+
+use crate::{boolean::Boolean, Vec};
+
+/// A reference to a child of a [`BddNode`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BddEdge {
+    /// A terminal with the given value.
+    Terminal(bool),
+    /// The node at the given index of the diagram.
+    Node(usize),
+}
+
+/// A decision node of a binary decision diagram, which evaluates to `high`
+/// if the input at index `var` is set, and to `low` otherwise.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BddNode {
+    /// The index of the input that this node branches on.
+    pub var: usize,
+    /// The child taken if the input is unset.
+    pub low: BddEdge,
+    /// The child taken if the input is set.
+    pub high: BddEdge,
+}
+
+/// A binary decision diagram, evaluated over `Boolean<F>` inputs.
+///
+/// The nodes are topologically ordered, i.e. the children of each node refer
+/// to nodes with smaller indices, so that the diagram can be evaluated
+/// bottom-up in a single pass.
+#[derive(Clone, Debug)]
+pub struct BddVar<F: PrimeField> {
+    nodes: Vec<BddNode>,
+    root: BddEdge,
+    _field: PhantomData<F>,
+}
+
+impl<F: PrimeField> BddVar<F> {
+    /// Constructs the diagram with the given `nodes` and `root`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a node refers to itself or to a later node, or if `root`
+    /// does not refer to a node of the diagram.
+    pub fn new(nodes: Vec<BddNode>, root: BddEdge) -> Self {
+        for (i, node) in nodes.iter().enumerate() {
+            for child in [node.low, node.high] {
+                assert!(!matches!(child, BddEdge::Node(j) if j >= i));
+            }
+        }
+        assert!(!matches!(root, BddEdge::Node(j) if j >= nodes.len()));
+        Self {
+            nodes,
+            root,
+            _field: PhantomData,
+        }
+    }
+
+    /// Outputs the value of the diagram on `inputs`.
+    ///
+    /// Each node is evaluated via a conditional selection on its input bit,
+    /// so this requires at most one constraint per node, and none for nodes
+    /// whose input is constant or whose children are both terminals.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a node branches on an input outside of `inputs`.
+    #[tracing::instrument(target = "gr1cs", skip(self, inputs))]
+    pub fn evaluate(&self, inputs: &[Boolean<F>]) -> Result<Boolean<F>, SynthesisError> {
+        let mut values: Vec<Boolean<F>> = Vec::with_capacity(self.nodes.len());
+        let resolve = |values: &[Boolean<F>], edge: BddEdge| match edge {
+            BddEdge::Terminal(b) => Boolean::Constant(b),
+            BddEdge::Node(i) => values[i].clone(),
+        };
+        for node in &self.nodes {
+            let high = resolve(&values, node.high);
+            let low = resolve(&values, node.low);
+            values.push(inputs[node.var].select(&high, &low)?);
+        }
+        Ok(resolve(&values, self.root))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BddEdge, BddNode, BddVar};
+    use crate::{alloc::AllocVar, boolean::Boolean, eq::EqGadget, GR1CSVar};
+    use ark_relations::gr1cs::ConstraintSystem;
+    use ark_test_curves::bls12_381::Fr;
+
+    /// Outputs the reduced diagram of the majority of three inputs.
+    fn majority() -> BddVar<Fr> {
+        use BddEdge::*;
+        let nodes = vec![
+            // `x2`
+            BddNode {
+                var: 2,
+                low: Terminal(false),
+                high: Terminal(true),
+            },
+            // `x1 & x2`
+            BddNode {
+                var: 1,
+                low: Terminal(false),
+                high: Node(0),
+            },
+            // `x1 | x2`
+            BddNode {
+                var: 1,
+                low: Node(0),
+                high: Terminal(true),
+            },
+            BddNode {
+                var: 0,
+                low: Node(1),
+                high: Node(2),
+            },
+        ];
+        BddVar::new(nodes, Node(3))
+    }
+
+    #[test]
+    fn test_evaluate_majority() {
+        let bdd = majority();
+        for assignment in 0..8u8 {
+            let bits = [0, 1, 2].map(|i| (assignment >> i) & 1 == 1);
+            let expected = bits.iter().filter(|b| **b).count() >= 2;
+
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let inputs = bits.map(|b| Boolean::new_witness(cs.clone(), || Ok(b)).unwrap());
+            let before = cs.num_constraints();
+            let output = bdd.evaluate(&inputs).unwrap();
+            assert_eq!(output.value().unwrap(), expected);
+            // One constraint per node, except for the terminal-only `x2`.
+            assert_eq!(cs.num_constraints() - before, 3);
+            assert!(cs.is_satisfied().unwrap());
+            output.enforce_equal(&Boolean::Constant(!expected)).unwrap();
+            assert!(!cs.is_satisfied().unwrap());
+
+            let constants = bits.map(Boolean::<Fr>::Constant);
+            assert_eq!(
+                bdd.evaluate(&constants).unwrap(),
+                Boolean::Constant(expected)
+            );
+        }
+    }
+}
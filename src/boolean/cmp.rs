@@ -1,4 +1,5 @@
 use crate::cmp::CmpGadget;
+use ark_ff::{BigInteger, BitIteratorLE};
 
 use super::*;
 
@@ -35,6 +36,94 @@ impl<F: PrimeField> Boolean<F> {
         Ok(())
     }
 
+    /// Enforces that `bits`, when interpreted as a little-endian integer, is
+    /// the canonical representation of a field element, i.e. is smaller than
+    /// `F::characteristic()`.
+    ///
+    /// This behaves like [`Self::enforce_in_field_le`], but also accepts
+    /// inputs shorter than `F::MODULUS_BIT_SIZE` bits, which are always
+    /// canonical. Bit decompositions produced by this crate (e.g. by
+    /// [`ToBitsGadget::to_bits_le`]) are already canonical; this check is
+    /// needed for decompositions that come from untrusted witnesses.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn enforce_bits_canonical(bits: &[Self]) -> Result<(), SynthesisError> {
+        if bits.len() < F::MODULUS_BIT_SIZE as usize {
+            return Ok(());
+        }
+        Self::enforce_in_field_le(bits)
+    }
+
+    /// Outputs the bit "`bits`, when interpreted as a little-endian integer,
+    /// is smaller than `F::characteristic()`".
+    ///
+    /// Unlike [`Self::enforce_bits_canonical`], this does not abort synthesis
+    /// (or make the constraint system unsatisfiable) for non-canonical
+    /// inputs, which allows branching on the result.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn bits_are_canonical(bits: &[Self]) -> Result<Self, SynthesisError> {
+        let modulus_bit_size = F::MODULUS_BIT_SIZE as usize;
+        if bits.len() < modulus_bit_size {
+            // `bits` is at most `2^(MODULUS_BIT_SIZE - 1) - 1 < p`.
+            return Ok(Self::TRUE);
+        }
+        let (low, high) = bits.split_at(modulus_bit_size);
+        let is_lt = Self::le_bits_lt_constant(low, F::characteristic());
+        if high.is_empty() {
+            Ok(is_lt)
+        } else {
+            Ok(is_lt & !Self::kary_or(high)?)
+        }
+    }
+
+    /// Outputs the bit "`bits`, when interpreted as a little-endian integer,
+    /// is smaller than `bound`".
+    ///
+    /// Only the lowest `bits.len()` bits of `bound` are compared, so callers
+    /// must ensure that `bound < 2^bits.len()`. Since `bound` is a constant,
+    /// this costs no more than two constraints per bit of `bits`.
+    pub(crate) fn le_bits_lt_constant(bits: &[Self], bound: impl AsRef<[u64]>) -> Self {
+        let bound_bits = BitIteratorLE::new(bound)
+            .take(bits.len())
+            .collect::<Vec<_>>();
+        // Scan from the most significant bit, keeping track of whether the
+        // bits seen so far are equal to those of `bound`. The first bit where
+        // they differ decides the comparison.
+        let mut is_lt = Self::FALSE;
+        let mut is_eq = Self::TRUE;
+        for (bit, bound_bit) in bits.iter().zip(bound_bits).rev() {
+            if bound_bit {
+                is_lt |= &is_eq & !bit;
+                is_eq &= bit;
+            } else {
+                is_eq &= !bit;
+            }
+        }
+        is_lt
+    }
+
+    /// Enforces that `bits`, when interpreted as a little-endian integer, is
+    /// smaller than `bound`.
+    ///
+    /// This generalizes [`Self::enforce_in_field_le`] to an arbitrary constant
+    /// ceiling, which is useful for range checks against bounds that are not
+    /// powers of two.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn enforce_bits_lt_constant(bits: &[Self], bound: F) -> Result<(), SynthesisError> {
+        if bound.is_zero() {
+            // No integer is smaller than zero.
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        // `bits` < `bound` <==> `bits` <= `bound - 1`
+        let max = (bound - F::one()).into_bigint();
+        if bits.len() < max.num_bits() as usize {
+            // `bits` is at most `2^bits.len() - 1`, which is already smaller
+            // than `bound`.
+            return Ok(());
+        }
+        let _ = Self::enforce_smaller_or_equal_than_le(bits, max)?;
+        Ok(())
+    }
+
     /// Enforces that `bits` is less than or equal to `element`,
     /// when both are interpreted as (little-endian) integers.
     #[tracing::instrument(target = "gr1cs", skip(element))]
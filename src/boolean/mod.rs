@@ -8,6 +8,7 @@ use core::borrow::Borrow;
 
 mod allocated;
 mod and;
+mod bdd;
 mod cmp;
 mod convert;
 mod eq;
@@ -17,6 +18,7 @@ mod select;
 mod xor;
 
 pub use allocated::AllocatedBool;
+pub use bdd::{BddEdge, BddNode, BddVar};
 
 #[cfg(test)]
 mod test_utils;
@@ -220,7 +222,8 @@ mod test {
     use super::Boolean;
     use crate::{convert::ToBytesGadget, prelude::*};
     use ark_ff::{
-        AdditiveGroup, BitIteratorBE, BitIteratorLE, Field, One, PrimeField, UniformRand,
+        AdditiveGroup, BigInt, BigInteger, BitIteratorBE, BitIteratorLE, Field, One, PrimeField,
+        UniformRand,
     };
     use ark_relations::gr1cs::{ConstraintSystem, SynthesisError};
     use ark_test_curves::bls12_381::Fr;
@@ -317,6 +320,66 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_enforce_bits_lt_constant() -> Result<(), SynthesisError> {
+        let bound = Fr::from(1000u64);
+        for (value, expected) in [
+            (0u64, true),
+            (999, true),
+            (1000, false),
+            (1001, false),
+            (1023, false),
+        ] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let bits = (0..10)
+                .map(|i| Boolean::new_witness(cs.clone(), || Ok((value >> i) & 1 == 1)))
+                .collect::<Result<Vec<_>, _>>()?;
+            Boolean::enforce_bits_lt_constant(&bits, bound)?;
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+
+        // Values that fit in fewer bits than the bound are always smaller.
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let bits = Vec::new_witness(cs.clone(), || Ok(vec![true; 4]))?;
+        Boolean::enforce_bits_lt_constant(&bits, bound)?;
+        assert!(cs.is_satisfied().unwrap());
+        Ok(())
+    }
+
+    #[test]
+    fn test_bits_canonical() -> Result<(), SynthesisError> {
+        let one = BigInt::from(1u64);
+        let modulus = Fr::MODULUS;
+        let (mut modulus_minus_one, mut modulus_plus_one) = (modulus, modulus);
+        modulus_minus_one.sub_with_borrow(&one);
+        modulus_plus_one.add_with_carry(&one);
+        let random = Fr::rand(&mut ark_std::test_rng()).into_bigint();
+        let num_bits = Fr::MODULUS_BIT_SIZE as usize;
+        for (value, len, expected) in [
+            (modulus_minus_one, num_bits, true),
+            (random, num_bits, true),
+            (modulus, num_bits, false),
+            (modulus_plus_one, num_bits, false),
+            (BigInt([u64::MAX; 4]), num_bits, false),
+            // Extra leading zeros are allowed, but extra leading ones are not.
+            (modulus_minus_one, num_bits + 1, true),
+            (BigInt([0, 0, 0, 1 << 63]), 256, false),
+            // Short inputs are always canonical.
+            (BigInt([u64::MAX; 4]), 10, true),
+        ] {
+            let bits = BitIteratorLE::new(value).take(len).collect::<Vec<_>>();
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let bits = Vec::new_witness(cs.clone(), || Ok(bits))?;
+            let is_canonical = Boolean::bits_are_canonical(&bits)?;
+            assert_eq!(is_canonical.value()?, expected);
+            assert!(cs.is_satisfied().unwrap());
+
+            Boolean::enforce_bits_canonical(&bits)?;
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+        Ok(())
+    }
+
     #[test]
     fn test_bits_to_fp() -> Result<(), SynthesisError> {
         use AllocationMode::*;
@@ -151,4 +151,21 @@ pub trait ToConstraintFieldGadget<ConstraintF: ark_ff::PrimeField> {
     fn to_constraint_field(
         &self,
     ) -> Result<Vec<crate::fields::fp::FpVar<ConstraintF>>, ark_relations::gr1cs::SynthesisError>;
+
+    /// Converts `self` to `FpVar<ConstraintF>` variables for absorption into
+    /// a cryptographic sponge.
+    ///
+    /// The layout matches the one used by `Absorb::to_sponge_field_elements`
+    /// when natively absorbing the corresponding value. By default, this is
+    /// the output of [`Self::to_constraint_field`], which matches native
+    /// absorption for field elements, booleans, single bytes, and curve
+    /// points. Types whose native absorption differs override this; e.g.,
+    /// byte strings are prefixed with their length as 8 little-endian bytes,
+    /// so that strings of different lengths are absorbed differently.
+    fn to_sponge_field_elements(
+        &self,
+    ) -> Result<Vec<crate::fields::fp::FpVar<ConstraintF>>, ark_relations::gr1cs::SynthesisError>
+    {
+        self.to_constraint_field()
+    }
 }
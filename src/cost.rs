@@ -0,0 +1,256 @@
+//! These functions let tooling estimate the size of a circuit without
+//! synthesizing it. Unless stated otherwise, the counts assume that all
+//! operands are non-constant; operations on constants are usually cheaper
+//! (and often free). The tests in this module synthesize each operation and
+//! check the advertised count against the actual one, so the counts stay in
+//! sync with the implementations.
+
+/// Constraint costs of operations on [`FpVar`](crate::fields::fp::FpVar).
+pub mod fp {
+    use ark_ff::PrimeField;
+
+    /// The cost of `a + b`, `a - b`, `-a`, and multiplication by a constant.
+    pub const fn linear() -> usize {
+        0
+    }
+
+    /// The cost of `a * b`.
+    pub const fn mul() -> usize {
+        1
+    }
+
+    /// The cost of `a.square()`.
+    pub const fn square() -> usize {
+        1
+    }
+
+    /// The cost of `a.inverse()`.
+    pub const fn inverse() -> usize {
+        1
+    }
+
+    /// The cost of `a.mul_equals(b, c)` and `a.square_equals(c)`.
+    pub const fn mul_equals() -> usize {
+        1
+    }
+
+    /// The cost of `a.enforce_equal(b)`.
+    pub const fn enforce_equal() -> usize {
+        1
+    }
+
+    /// The cost of `a.is_eq(b)` and `a.is_neq(b)`.
+    pub const fn is_eq() -> usize {
+        2
+    }
+
+    /// The cost of `cond.select(a, b)`.
+    pub const fn select() -> usize {
+        1
+    }
+
+    /// The cost of `a.to_bits_le_with_top_bits_zero(size)`: one booleanity
+    /// constraint per bit, and one constraint for the reconstruction.
+    pub const fn to_bits_le_with_top_bits_zero(size: usize) -> usize {
+        size + 1
+    }
+
+    /// The cost of `a.to_bits_le_with_top_bits_zero_or_full(size)`. If
+    /// `size == F::MODULUS_BIT_SIZE`, this is the cost of [`to_bits_le`].
+    pub fn to_bits_le_with_top_bits_zero_or_full<F: PrimeField>(size: usize) -> usize {
+        if size == F::MODULUS_BIT_SIZE as usize {
+            to_bits_le::<F>()
+        } else {
+            to_bits_le_with_top_bits_zero(size)
+        }
+    }
+
+    /// The cost of `a.to_non_unique_bits_le()`: one booleanity constraint per
+    /// bit, and one constraint for the reconstruction.
+    pub fn to_non_unique_bits_le<F: PrimeField>() -> usize {
+        F::MODULUS_BIT_SIZE as usize + 1
+    }
+
+    /// The cost of `a.to_bits_le()`, which additionally checks that the
+    /// decomposition is canonical via [`super::boolean::enforce_in_field_le`].
+    pub fn to_bits_le<F: PrimeField>() -> usize {
+        to_non_unique_bits_le::<F>() + super::boolean::enforce_in_field_le::<F>()
+    }
+}
+
+/// Constraint costs of operations on [`Boolean`](crate::boolean::Boolean).
+pub mod boolean {
+    use ark_ff::{BitIteratorBE, PrimeField};
+
+    /// The cost of `!a`.
+    pub const fn not() -> usize {
+        0
+    }
+
+    /// The cost of `a & b`, `a | b` and `a ^ b`.
+    pub const fn binary_op() -> usize {
+        1
+    }
+
+    /// The cost of `a.enforce_equal(b)`.
+    pub const fn enforce_equal() -> usize {
+        1
+    }
+
+    /// The cost of `Boolean::kary_and(bits)` and `Boolean::kary_or(bits)`
+    /// on `n` non-constant bits.
+    pub const fn kary_op(n: usize) -> usize {
+        if n <= 3 {
+            n.saturating_sub(1)
+        } else {
+            2
+        }
+    }
+
+    /// The cost of `Boolean::enforce_in_field_le(bits)` on
+    /// `F::MODULUS_BIT_SIZE` non-constant bits.
+    ///
+    /// This depends on the runs of ones in the binary expansion of
+    /// `F::characteristic() - 1`.
+    pub fn enforce_in_field_le<F: PrimeField>() -> usize {
+        // This mirrors `Boolean::enforce_smaller_or_equal_than_le`: each run of
+        // ones is combined (together with the result of the previous run)
+        // via `kary_and`, and each zero bit requires a `kary_nand` with the
+        // previous run. The initial "previous run" is the constant `TRUE`.
+        let mut bound = F::characteristic().to_vec();
+        // The characteristic is odd, so this does not borrow.
+        bound[0] -= 1;
+
+        let mut constraints = 0;
+        let mut run = 0;
+        let mut last_run_is_constant = true;
+        for bit in BitIteratorBE::without_leading_zeros(&bound) {
+            if bit {
+                run += 1;
+                continue;
+            }
+            if run > 0 {
+                // If the run has at least three bits, `kary_and` uses the
+                // summation approach regardless of whether `last_run` is
+                // constant; otherwise, constant operands are free.
+                constraints += kary_op(run + usize::from(!last_run_is_constant));
+                last_run_is_constant = false;
+                run = 0;
+            }
+            // `kary_and(&[last_run, bit])`, and enforcing the result to be
+            // `false`.
+            let nand = if last_run_is_constant { 0 } else { 1 };
+            constraints += nand + enforce_equal();
+        }
+        constraints
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{boolean, fp};
+    use crate::{
+        alloc::AllocVar,
+        boolean::Boolean,
+        convert::ToBitsGadget,
+        eq::EqGadget,
+        fields::{fp::FpVar, FieldVar},
+        select::CondSelectGadget,
+    };
+    use ark_ff::PrimeField;
+    use ark_relations::gr1cs::{ConstraintSystem, ConstraintSystemRef};
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::Fr;
+
+    /// Outputs the number of constraints generated by `op`.
+    fn cost<F: PrimeField>(cs: &ConstraintSystemRef<F>, op: impl FnOnce()) -> usize {
+        let before = cs.num_constraints();
+        op();
+        cs.num_constraints() - before
+    }
+
+    #[test]
+    fn test_fp_costs() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let a = FpVar::new_witness(cs.clone(), || Ok(Fr::rand(&mut rng))).unwrap();
+        let b = FpVar::new_witness(cs.clone(), || Ok(Fr::rand(&mut rng))).unwrap();
+        let c = FpVar::new_witness(cs.clone(), || Ok(Fr::rand(&mut rng))).unwrap();
+        let cond = Boolean::new_witness(cs.clone(), || Ok(true)).unwrap();
+
+        let linear = cost(&cs, || {
+            let _ = (&a + &b - &c).negate().unwrap() * Fr::from(3u64);
+        });
+        assert_eq!(linear, fp::linear());
+        assert_eq!(cost(&cs, || drop(&a * &b)), fp::mul());
+        assert_eq!(cost(&cs, || drop(a.square().unwrap())), fp::square());
+        assert_eq!(cost(&cs, || drop(a.inverse().unwrap())), fp::inverse());
+        let mul_equals = cost(&cs, || a.mul_equals(&b, &c).unwrap());
+        assert_eq!(mul_equals, fp::mul_equals());
+        let square_equals = cost(&cs, || a.square_equals(&c).unwrap());
+        assert_eq!(square_equals, fp::mul_equals());
+        let enforce_equal = cost(&cs, || a.enforce_equal(&b).unwrap());
+        assert_eq!(enforce_equal, fp::enforce_equal());
+        assert_eq!(cost(&cs, || drop(a.is_eq(&b).unwrap())), fp::is_eq());
+        assert_eq!(cost(&cs, || drop(a.is_neq(&b).unwrap())), fp::is_eq());
+        let select = cost(&cs, || drop(FpVar::conditionally_select(&cond, &a, &b)));
+        assert_eq!(select, fp::select());
+        let full_size = Fr::MODULUS_BIT_SIZE as usize;
+        for size in [1, 8, 64, full_size - 1] {
+            let top_bits_zero = cost(&cs, || drop(a.to_bits_le_with_top_bits_zero(size)));
+            assert_eq!(top_bits_zero, fp::to_bits_le_with_top_bits_zero(size));
+        }
+        for size in [64, full_size] {
+            let or_full = cost(&cs, || drop(a.to_bits_le_with_top_bits_zero_or_full(size)));
+            assert_eq!(
+                or_full,
+                fp::to_bits_le_with_top_bits_zero_or_full::<Fr>(size)
+            );
+        }
+    }
+
+    fn check_to_bits_costs<F: PrimeField>() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<F>::new_ref();
+        let a = FpVar::new_witness(cs.clone(), || Ok(F::rand(&mut rng))).unwrap();
+        let non_unique = cost(&cs, || drop(a.to_non_unique_bits_le().unwrap()));
+        assert_eq!(non_unique, fp::to_non_unique_bits_le::<F>());
+        assert_eq!(
+            cost(&cs, || drop(a.to_bits_le().unwrap())),
+            fp::to_bits_le::<F>()
+        );
+
+        let bits = a.to_non_unique_bits_le().unwrap();
+        let in_field = cost(&cs, || Boolean::enforce_in_field_le(&bits).unwrap());
+        assert_eq!(in_field, boolean::enforce_in_field_le::<F>());
+    }
+
+    #[test]
+    fn test_to_bits_costs() {
+        check_to_bits_costs::<Fr>();
+        check_to_bits_costs::<ark_bls12_381::Fq>();
+        check_to_bits_costs::<ark_bn254::Fr>();
+        check_to_bits_costs::<ark_mnt4_298::Fr>();
+    }
+
+    #[test]
+    fn test_boolean_costs() {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let bits = [true, false, true, true, false]
+            .map(|b| Boolean::new_witness(cs.clone(), || Ok(b)).unwrap());
+        let [a, b, ..] = &bits;
+
+        assert_eq!(cost(&cs, || drop(!a)), boolean::not());
+        assert_eq!(cost(&cs, || drop(a & b)), boolean::binary_op());
+        assert_eq!(cost(&cs, || drop(a | b)), boolean::binary_op());
+        assert_eq!(cost(&cs, || drop(a ^ b)), boolean::binary_op());
+        let enforce_equal = cost(&cs, || a.enforce_equal(&bits[2]).unwrap());
+        assert_eq!(enforce_equal, boolean::enforce_equal());
+        for n in 1..=bits.len() {
+            let and = cost(&cs, || drop(Boolean::kary_and(&bits[..n]).unwrap()));
+            assert_eq!(and, boolean::kary_op(n));
+            let or = cost(&cs, || drop(Boolean::kary_or(&bits[..n]).unwrap()));
+            assert_eq!(or, boolean::kary_op(n));
+        }
+    }
+}
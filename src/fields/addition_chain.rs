@@ -0,0 +1,210 @@
+use ark_ff::{BitIteratorBE, Field, PrimeField};
+use ark_relations::gr1cs::SynthesisError;
+
+use crate::{fields::FieldVar, Vec};
+
+/// The largest window width considered by [`AdditionChain::new`].
+const MAX_WINDOW: usize = 5;
+
+/// A precomputed schedule of squarings and multiplications for computing
+/// `x^e` for a fixed exponent `e`.
+///
+/// Computing the schedule once and reusing it via [`Self::pow`] avoids
+/// recomputing it for every variable raised to the same power, e.g. in the
+/// S-boxes of a hash function.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AdditionChain {
+    /// Each step multiplies two earlier elements of the chain, which starts
+    /// with `x` itself. A step `(i, i)` is a squaring.
+    steps: Vec<(usize, usize)>,
+    /// Whether the exponent is zero, in which case the chain outputs one.
+    is_zero: bool,
+}
+
+impl AdditionChain {
+    /// Computes an addition chain for the exponent `exp`, which is
+    /// interpreted as a little-endian `u64`-decomposition of an integer.
+    ///
+    /// This uses the sliding-window method, and picks the window width
+    /// that results in the fewest multiplications.
+    pub fn new(exp: &[u64]) -> Self {
+        let bits = BitIteratorBE::without_leading_zeros(exp).collect::<Vec<_>>();
+        if bits.is_empty() {
+            return Self {
+                steps: Vec::new(),
+                is_zero: true,
+            };
+        }
+        (1..=MAX_WINDOW)
+            .map(|window| Self::sliding_window(&bits, window))
+            .min_by_key(|chain| chain.steps.len())
+            .unwrap()
+    }
+
+    /// Builds the chain for the big-endian exponent `bits` with a window of
+    /// width `window`. The first bit of `bits` must be set.
+    fn sliding_window(bits: &[bool], window: usize) -> Self {
+        let mut steps = Vec::new();
+        let mut push = |step: (usize, usize)| {
+            steps.push(step);
+            steps.len()
+        };
+        // `odd_powers[k]` is the index of `x^(2k + 1)`.
+        let mut odd_powers = vec![0];
+        if window > 1 {
+            let square = push((0, 0));
+            for k in 1..1 << (window - 1) {
+                odd_powers.push(push((odd_powers[k - 1], square)));
+            }
+        }
+
+        let mut acc: Option<usize> = None;
+        let mut i = 0;
+        while i < bits.len() {
+            if !bits[i] {
+                // The leading bit is set, so `acc` has been initialized.
+                let a = acc.unwrap();
+                acc = Some(push((a, a)));
+                i += 1;
+                continue;
+            }
+            // The longest window starting at `i` that ends in a set bit.
+            let len = (1..=window.min(bits.len() - i))
+                .rev()
+                .find(|&len| bits[i + len - 1])
+                .unwrap();
+            let value = bits[i..i + len]
+                .iter()
+                .fold(0usize, |value, &bit| (value << 1) | usize::from(bit));
+            let power = odd_powers[value >> 1];
+            acc = Some(match acc {
+                Some(mut a) => {
+                    for _ in 0..len {
+                        a = push((a, a));
+                    }
+                    push((a, power))
+                },
+                None => power,
+            });
+            i += len;
+        }
+
+        // Drop the precomputed powers that the window did not need, as an
+        // unused step still costs a multiplication.
+        let result = acc.unwrap();
+        let mut used = vec![false; steps.len() + 1];
+        used[result] = true;
+        for (idx, &(a, b)) in steps.iter().enumerate().rev() {
+            if used[idx + 1] {
+                used[a] = true;
+                used[b] = true;
+            }
+        }
+        let mut new_index = vec![0; steps.len() + 1];
+        let mut pruned = Vec::new();
+        for (idx, &(a, b)) in steps.iter().enumerate() {
+            if used[idx + 1] {
+                pruned.push((new_index[a], new_index[b]));
+                new_index[idx + 1] = pruned.len();
+            }
+        }
+        Self {
+            steps: pruned,
+            is_zero: false,
+        }
+    }
+
+    /// Outputs the number of squarings and multiplications in the chain.
+    pub fn num_multiplications(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Computes `x^e`, where `e` is the exponent of this chain.
+    #[tracing::instrument(target = "gr1cs", skip(self, x))]
+    pub fn pow<F: Field, ConstraintF: PrimeField, V: FieldVar<F, ConstraintF>>(
+        &self,
+        x: &V,
+    ) -> Result<V, SynthesisError> {
+        if self.is_zero {
+            return Ok(V::one());
+        }
+        let mut elements = Vec::with_capacity(self.steps.len() + 1);
+        elements.push(x.clone());
+        for &(a, b) in &self.steps {
+            let element = if a == b {
+                elements[a].square()?
+            } else {
+                elements[a].clone() * &elements[b]
+            };
+            elements.push(element);
+        }
+        Ok(elements.pop().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::AdditionChain;
+    use crate::{
+        alloc::AllocVar,
+        fields::{fp::FpVar, FieldVar},
+        GR1CSVar,
+    };
+    use ark_ff::Field;
+    use ark_relations::gr1cs::ConstraintSystem;
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::Fr;
+
+    #[test]
+    fn test_addition_chain() {
+        let mut rng = ark_std::test_rng();
+        let base = Fr::rand(&mut rng);
+        for exp in [0u64, 1, 2, 3, 5, 7, 17, 255, 0x1234_5678, u64::MAX] {
+            let chain = AdditionChain::new(&[exp]);
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let x = FpVar::new_witness(cs.clone(), || Ok(base)).unwrap();
+            let constraints = cs.num_constraints();
+            let result = chain.pow(&x).unwrap();
+            assert_eq!(result.value().unwrap(), base.pow([exp]));
+            assert_eq!(
+                cs.num_constraints() - constraints,
+                chain.num_multiplications()
+            );
+
+            // The chain is never longer than square-and-multiply.
+            let constraints = cs.num_constraints();
+            x.pow_by_constant([exp]).unwrap();
+            assert!(chain.num_multiplications() <= cs.num_constraints() - constraints);
+            assert!(cs.is_satisfied().unwrap());
+        }
+        // Square-and-multiply needs 126 steps for `x^(2^64 - 1)`, while
+        // windows of four bits need only the 8 steps for `x^15`, plus 60
+        // squarings and 15 multiplications.
+        assert_eq!(AdditionChain::new(&[5]).num_multiplications(), 3);
+        assert_eq!(AdditionChain::new(&[u64::MAX]).num_multiplications(), 83);
+    }
+
+    #[test]
+    fn test_pow_by_constant_in_place() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let exp = [17u64, 3];
+        let chain = AdditionChain::new(&exp);
+        let mut costs = Vec::new();
+        for _ in 0..4 {
+            let value = Fr::rand(&mut rng);
+            let mut x = FpVar::new_witness(cs.clone(), || Ok(value)).unwrap();
+            let expected = x.pow_by_constant(exp).unwrap();
+            x.pow_by_constant_in_place(&exp).unwrap();
+            assert_eq!(x.value().unwrap(), expected.value().unwrap());
+            assert_eq!(x.value().unwrap(), value.pow(exp));
+
+            let constraints = cs.num_constraints();
+            let reused = chain.pow(&x).unwrap();
+            costs.push(cs.num_constraints() - constraints);
+            assert_eq!(reused.value().unwrap(), value.pow(exp).pow(exp));
+        }
+        assert!(costs.iter().all(|&cost| cost == costs[0]));
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
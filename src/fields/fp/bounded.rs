@@ -0,0 +1,733 @@
+use crate::{fields::fp::FpVar, prelude::*, Vec};
+use ark_ff::{BigInteger, PrimeField};
+use ark_relations::gr1cs::SynthesisError;
+
+impl<F: PrimeField> FpVar<F> {
+    /// Enforces that `self`, interpreted as an integer, is smaller than
+    /// `2^bit_size`.
+    ///
+    /// This requires `bit_size + 1` constraints.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn enforce_bit_size(&self, bit_size: usize) -> Result<(), SynthesisError> {
+        let _ = self.to_bits_le_with_top_bits_zero(bit_size)?;
+        Ok(())
+    }
+
+    /// Outputs the decomposition of `self` into little-endian limbs of
+    /// `num_bits_per_limb` bits each, such that
+    /// `self == sum_i limbs[i] * 2^(i * num_bits_per_limb)`.
+    ///
+    /// The limbs are packed from the canonical bit decomposition of `self`,
+    /// so each limb is range-checked by construction, and the decomposition
+    /// is unique. There are `ceil(F::MODULUS_BIT_SIZE / num_bits_per_limb)`
+    /// limbs, and this requires the constraints of
+    /// [`ToBitsGadget::to_bits_le`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_bits_per_limb == 0`.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn to_limbs(&self, num_bits_per_limb: usize) -> Result<Vec<Self>, SynthesisError> {
+        assert!(num_bits_per_limb > 0);
+        self.to_bits_le()?
+            .chunks(num_bits_per_limb)
+            .map(Boolean::le_bits_to_fp)
+            .collect()
+    }
+
+    /// Outputs `sum_i limbs[i] * 2^(i * num_bits_per_limb)`, after enforcing
+    /// that every limb is smaller than `2^num_bits_per_limb`.
+    ///
+    /// This is the inverse of [`Self::to_limbs`]. Note that if
+    /// `limbs.len() * num_bits_per_limb >= F::MODULUS_BIT_SIZE`, distinct
+    /// limbs may recombine to the same field element.
+    ///
+    /// Each limb requires `num_bits_per_limb + 1` constraints, and the
+    /// recombination itself is a single linear combination.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_bits_per_limb` is zero or exceeds
+    /// `F::MODULUS_BIT_SIZE`.
+    #[tracing::instrument(target = "gr1cs", skip(limbs))]
+    pub fn from_limbs(limbs: &[Self], num_bits_per_limb: usize) -> Result<Self, SynthesisError> {
+        assert!(num_bits_per_limb > 0);
+        let shift = super::power_of_two::<F>(num_bits_per_limb as u32);
+        let mut weights = Vec::with_capacity(limbs.len());
+        let mut weight = F::one();
+        for limb in limbs {
+            limb.enforce_bit_size(num_bits_per_limb)?;
+            weights.push(weight);
+            weight *= shift;
+        }
+        Self::sparse_weighted_sum(&weights, limbs)
+    }
+
+    /// Outputs the bit `self <= other`, where `self` and `other` are
+    /// interpreted as integers in `[0, 2^bit_size)`.
+    ///
+    /// This function assumes that `self` and `other` are smaller than
+    /// `2^bit_size` and does not generate constraints to verify that.
+    /// The result is meaningless if that is not the case.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn is_le_bounded(
+        &self,
+        other: &Self,
+        bit_size: usize,
+    ) -> Result<Boolean<F>, SynthesisError> {
+        // `other - self + 2^bit_size` lies in `[1, 2^(bit_size + 1))`,
+        // and its top bit is set exactly when `other >= self`.
+        Self::top_bit_of_shifted(&(other - self), bit_size)
+    }
+
+    /// Outputs the bit `self < other`, where `self` and `other` are
+    /// interpreted as integers in `[0, 2^bit_size)`.
+    ///
+    /// This function assumes that `self` and `other` are smaller than
+    /// `2^bit_size` and does not generate constraints to verify that.
+    /// The result is meaningless if that is not the case.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn is_lt_bounded(
+        &self,
+        other: &Self,
+        bit_size: usize,
+    ) -> Result<Boolean<F>, SynthesisError> {
+        // `other - self - 1 + 2^bit_size` lies in `[0, 2^(bit_size + 1))`,
+        // and its top bit is set exactly when `other > self`.
+        Self::top_bit_of_shifted(&(other - self - F::one()), bit_size)
+    }
+
+    /// Outputs the `bit_size`-th bit of `diff + 2^bit_size`, which is set
+    /// if and only if `diff`, read as a signed integer in
+    /// `(-2^bit_size, 2^bit_size)`, is non-negative.
+    fn top_bit_of_shifted(diff: &Self, bit_size: usize) -> Result<Boolean<F>, SynthesisError> {
+        assert!(bit_size + 1 < F::MODULUS_BIT_SIZE as usize);
+        let shifted = diff + F::from(2u64).pow([bit_size as u64]);
+        let (bits, _) = shifted.to_bits_le_with_top_bits_zero(bit_size + 1)?;
+        Ok(bits[bit_size].clone())
+    }
+
+    /// Outputs `min(self + other, max)`, where `self` and `other` are
+    /// interpreted as integers in `[0, 2^bit_size)`.
+    ///
+    /// This method enforces that `self` and `other` are smaller than
+    /// `2^bit_size`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max >= 2^(bit_size + 1)`.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn saturating_add(
+        &self,
+        other: &Self,
+        max: F,
+        bit_size: usize,
+    ) -> Result<Self, SynthesisError> {
+        assert!(max.into_bigint().num_bits() as usize <= bit_size + 1);
+        self.enforce_bit_size(bit_size)?;
+        other.enforce_bit_size(bit_size)?;
+
+        // The sum cannot overflow `bit_size + 1` bits.
+        let sum = self + other;
+        let max = Self::constant(max);
+        let fits = sum.is_le_bounded(&max, bit_size + 1)?;
+        fits.select(&sum, &max)
+    }
+
+    /// Outputs `(self + other) mod 2^n` and the bit `self + other >= 2^n`,
+    /// where `self` and `other` are interpreted as integers in `[0, 2^n)`.
+    ///
+    /// This mirrors checked addition of `n`-bit unsigned integers: the first
+    /// output is the wrapped sum, and the second is the overflow flag. This
+    /// method enforces that `self` and `other` are smaller than `2^n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n + 1 >= F::MODULUS_BIT_SIZE`.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn checked_add_bounded(
+        &self,
+        other: &Self,
+        n: usize,
+    ) -> Result<(Self, Boolean<F>), SynthesisError> {
+        assert!(n + 1 < F::MODULUS_BIT_SIZE as usize);
+        self.enforce_bit_size(n)?;
+        other.enforce_bit_size(n)?;
+
+        // The sum cannot overflow `n + 1` bits, and its top bit is the
+        // overflow flag.
+        let (mut bits, _) = (self + other).to_bits_le_with_top_bits_zero(n + 1)?;
+        let overflow = bits.pop().unwrap();
+        Ok((Boolean::le_bits_to_fp(&bits)?, overflow))
+    }
+
+    /// Enforces that `value` lies within `percent` percent of `reference`,
+    /// i.e. that `|value - reference| * 100 <= percent * reference`, where
+    /// `value` and `reference` are interpreted as integers in
+    /// `[0, 2^bit_size)`.
+    ///
+    /// This method enforces that `value` and `reference` are smaller than
+    /// `2^bit_size`. Both sides of the inequality are then smaller than
+    /// `2^product_size`, where `product_size` is `bit_size` plus the bit size
+    /// of the larger of `100` and `percent`, and the inequality is checked by
+    /// enforcing that `percent * reference - |value - reference| * 100` fits
+    /// into `product_size` bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `product_size + 1 >= F::MODULUS_BIT_SIZE`, which happens for
+    /// large `percent` or `bit_size`.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn enforce_within_percent(
+        value: &Self,
+        reference: &Self,
+        percent: u64,
+        bit_size: usize,
+    ) -> Result<(), SynthesisError> {
+        let percent_bits = (u64::BITS - percent.leading_zeros()) as usize;
+        let product_size = bit_size + ark_std::cmp::max(7, percent_bits);
+        // Negative differences must not fit into `product_size` bits.
+        assert!(product_size + 1 < F::MODULUS_BIT_SIZE as usize);
+        value.enforce_bit_size(bit_size)?;
+        reference.enforce_bit_size(bit_size)?;
+
+        let value_is_larger = reference.is_le_bounded(value, bit_size)?;
+        let difference = value_is_larger.select(&(value - reference), &(reference - value))?;
+
+        let scaled_difference = difference * F::from(100u64);
+        let tolerance = reference * F::from(percent);
+        (tolerance - scaled_difference).enforce_bit_size(product_size)
+    }
+
+    /// Enforces that `|noisy_value - true_value| <= noise_bound`, i.e. that
+    /// the noise added to `true_value` is within the public `noise_bound`,
+    /// where `true_value` and `noisy_value` are interpreted as integers in
+    /// `[0, 2^bit_size)`.
+    ///
+    /// This method enforces that `true_value` and `noisy_value` are smaller
+    /// than `2^bit_size`. The signed difference `noise` then lies in
+    /// `(-2^bit_size, 2^bit_size)`, and the bound is checked by enforcing that
+    /// both `noise_bound - noise` and `noise_bound + noise` are non-negative.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `noise_bound >= 2^bit_size`, or if
+    /// `bit_size + 2 >= F::MODULUS_BIT_SIZE`.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn enforce_clamped_noisy(
+        true_value: &Self,
+        noisy_value: &Self,
+        noise_bound: F,
+        bit_size: usize,
+    ) -> Result<(), SynthesisError> {
+        assert!(noise_bound.into_bigint().num_bits() as usize <= bit_size);
+        assert!(bit_size + 2 < F::MODULUS_BIT_SIZE as usize);
+        true_value.enforce_bit_size(bit_size)?;
+        noisy_value.enforce_bit_size(bit_size)?;
+
+        // Both sums lie in `(-2^bit_size, 2^(bit_size + 1))`, and negative
+        // sums wrap around to field elements larger than `2^(bit_size + 1)`.
+        let noise = noisy_value - true_value;
+        (&noise + noise_bound).enforce_bit_size(bit_size + 1)?;
+        (Self::constant(noise_bound) - &noise).enforce_bit_size(bit_size + 1)
+    }
+
+    /// Enforces that `x = a^2 + b^2 + c^2 + d^2` over the integers, which, by
+    /// Lagrange's four-square theorem, proves that `x` is a non-negative
+    /// integer.
+    ///
+    /// To rule out wrap-around modulo the field characteristic, this method
+    /// enforces that `a`, `b`, `c` and `d` are smaller than
+    /// `2^((F::MODULUS_BIT_SIZE - 3) / 2)`, so that the sum of squares is
+    /// smaller than `2^(F::MODULUS_BIT_SIZE - 1)`. Hence, `x` is a
+    /// non-negative integer in the signed encoding `[-(p-1)/2, (p-1)/2]`.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn enforce_nonnegative_four_square(
+        x: &Self,
+        a: &Self,
+        b: &Self,
+        c: &Self,
+        d: &Self,
+    ) -> Result<(), SynthesisError> {
+        let bit_size = (F::MODULUS_BIT_SIZE as usize - 3) / 2;
+        let mut sum = Self::zero();
+        for v in [a, b, c, d] {
+            v.enforce_bit_size(bit_size)?;
+            sum += v.square()?;
+        }
+        sum.enforce_equal(x)
+    }
+
+    /// Enforces that `keys` is sorted in non-decreasing order, where the keys
+    /// are interpreted as integers in `[0, 2^bit_size)`.
+    ///
+    /// Equal keys are allowed, so this accepts the output of a stable sort.
+    /// This method enforces that each key is smaller than `2^bit_size`, and
+    /// requires roughly `2 * bit_size` constraints per key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bit_size + 1 >= F::MODULUS_BIT_SIZE`.
+    #[tracing::instrument(target = "gr1cs", skip(keys))]
+    pub fn enforce_sorted_by_key(keys: &[Self], bit_size: usize) -> Result<(), SynthesisError> {
+        assert!(bit_size + 1 < F::MODULUS_BIT_SIZE as usize);
+        for key in keys {
+            key.enforce_bit_size(bit_size)?;
+        }
+        // If `next < prev`, then `next - prev` wraps around to a field element
+        // larger than `p - 2^bit_size > 2^bit_size`.
+        for pair in keys.windows(2) {
+            (&pair[1] - &pair[0]).enforce_bit_size(bit_size)?;
+        }
+        Ok(())
+    }
+
+    /// Enforces that `sorted_nonces` is exactly the gapless run
+    /// `start, start + 1, ..., start + n - 1`, where `n = sorted_nonces.len()`
+    /// and all values are interpreted as integers in `[0, 2^bit_size)`.
+    ///
+    /// This checks that the first nonce equals `start` and that each nonce
+    /// is one more than its predecessor. Additionally, `start` and the last
+    /// nonce are enforced to be smaller than `2^bit_size`, so that the run
+    /// cannot wrap around the modulus.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bit_size >= F::MODULUS_BIT_SIZE`.
+    #[tracing::instrument(target = "gr1cs", skip(sorted_nonces))]
+    pub fn enforce_no_gaps(
+        sorted_nonces: &[Self],
+        start: &Self,
+        bit_size: usize,
+    ) -> Result<(), SynthesisError> {
+        start.enforce_bit_size(bit_size)?;
+        let (first, last) = match (sorted_nonces.first(), sorted_nonces.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return Ok(()),
+        };
+        first.enforce_equal(start)?;
+        for pair in sorted_nonces.windows(2) {
+            pair[1].enforce_equal(&(&pair[0] + F::one()))?;
+        }
+        last.enforce_bit_size(bit_size)
+    }
+
+    /// Enforces that `sum(inputs) == sum(outputs) + fee`, where all amounts
+    /// are interpreted as integers in `[0, 2^bit_size)`.
+    ///
+    /// Each amount is enforced to be smaller than `2^bit_size`, so that
+    /// neither side of the equation can wrap around the modulus. Both sums are
+    /// linear combinations, so the balance itself requires a single
+    /// constraint.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sums of up to `inputs.len() + outputs.len() + 1`
+    /// amounts could overflow the modulus.
+    #[tracing::instrument(target = "gr1cs", skip(inputs, outputs))]
+    pub fn enforce_value_conservation(
+        inputs: &[Self],
+        outputs: &[Self],
+        fee: &Self,
+        bit_size: usize,
+    ) -> Result<(), SynthesisError> {
+        let num_amounts = inputs.len() + outputs.len() + 1;
+        assert!(bit_size + (ark_std::log2(num_amounts) as usize) < F::MODULUS_BIT_SIZE as usize);
+        for amount in inputs.iter().chain(outputs).chain([fee]) {
+            amount.enforce_bit_size(bit_size)?;
+        }
+        let total_in: Self = inputs.iter().sum();
+        let total_out: Self = outputs.iter().sum();
+        total_in.enforce_equal(&(total_out + fee))
+    }
+
+    /// Enforces that `claimed_mean` is the mean of `values` after discarding
+    /// the `trim` smallest and the `trim` largest values, where `values` are
+    /// interpreted as integers in `[0, 2^bit_size)`.
+    ///
+    /// To avoid division, this enforces that
+    /// `claimed_mean * (values.len() - 2 * trim)` equals the sum of the
+    /// remaining values. If the count does not divide the sum, the only
+    /// accepted `claimed_mean` is the corresponding field element, which is
+    /// not an integer.
+    ///
+    /// This method enforces that each value is smaller than `2^bit_size`, and
+    /// sorts the values in-circuit via an odd-even transposition network with
+    /// `n * (n - 1) / 2` comparators of `bit_size + 3` constraints each, so it
+    /// is only suited to small inputs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `2 * trim >= values.len()`, or if
+    /// `bit_size + 1 >= F::MODULUS_BIT_SIZE`.
+    #[tracing::instrument(target = "gr1cs", skip(values))]
+    pub fn enforce_trimmed_mean(
+        values: &[Self],
+        trim: usize,
+        claimed_mean: &Self,
+        bit_size: usize,
+    ) -> Result<(), SynthesisError> {
+        assert!(2 * trim < values.len());
+        assert!(bit_size + 1 < F::MODULUS_BIT_SIZE as usize);
+        for value in values {
+            value.enforce_bit_size(bit_size)?;
+        }
+        let sorted = Self::sort_bounded(values, bit_size)?;
+        let kept = &sorted[trim..values.len() - trim];
+        let sum: Self = kept.iter().sum();
+        (claimed_mean * F::from(kept.len() as u64)).enforce_equal(&sum)
+    }
+
+    /// Outputs `values` sorted in non-decreasing order, where `values` are
+    /// interpreted as integers in `[0, 2^bit_size)`.
+    ///
+    /// This function assumes that `values` are smaller than `2^bit_size`.
+    fn sort_bounded(values: &[Self], bit_size: usize) -> Result<Vec<Self>, SynthesisError> {
+        let mut sorted = values.to_vec();
+        // Odd-even transposition sort: `n` rounds of compare-and-swap on
+        // alternating adjacent pairs sort any input.
+        for round in 0..sorted.len() {
+            for i in (round % 2..sorted.len().saturating_sub(1)).step_by(2) {
+                let (a, b) = (&sorted[i], &sorted[i + 1]);
+                let in_order = a.is_le_bounded(b, bit_size)?;
+                let min = in_order.select(a, b)?;
+                let max = a + b - &min;
+                sorted[i] = min;
+                sorted[i + 1] = max;
+            }
+        }
+        Ok(sorted)
+    }
+
+    /// Enforces that `sorted` is a permutation of the `(key, payload)` rows in
+    /// `rows` that is sorted by key in non-decreasing order, where the keys are
+    /// interpreted as integers in `[0, 2^bit_size)`.
+    ///
+    /// The keys of `sorted` are checked via [`Self::enforce_sorted_by_key`].
+    /// That `sorted` is a permutation of `rows` is checked by compressing each
+    /// row to `key + alpha * payload`, and applying
+    /// [`Self::enforce_multiset_equal`] with challenge `beta`.
+    /// For this to be sound, `alpha` and `beta` must be random challenges
+    /// that are chosen after `rows` and `sorted` have been fixed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rows` and `sorted` have different lengths, or if
+    /// `bit_size + 1 >= F::MODULUS_BIT_SIZE`.
+    #[tracing::instrument(target = "gr1cs", skip(rows, sorted))]
+    pub fn enforce_sorted_by_key_with_payload(
+        rows: &[(Self, Self)],
+        sorted: &[(Self, Self)],
+        bit_size: usize,
+        alpha: &Self,
+        beta: &Self,
+    ) -> Result<(), SynthesisError> {
+        assert_eq!(rows.len(), sorted.len());
+        let keys = sorted
+            .iter()
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        Self::enforce_sorted_by_key(&keys, bit_size)?;
+
+        let compress = |rows: &[(Self, Self)]| {
+            rows.iter()
+                .map(|(key, payload)| key + alpha * payload)
+                .collect::<Vec<_>>()
+        };
+        Self::enforce_multiset_equal(&compress(rows), &compress(sorted), beta)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{alloc::AllocVar, eq::EqGadget, fields::fp::FpVar, GR1CSVar};
+    use ark_ff::{BigInteger, PrimeField};
+    use ark_relations::gr1cs::ConstraintSystem;
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::Fr;
+
+    #[test]
+    fn test_saturating_add() {
+        let max = Fr::from(255u64);
+        for (a, b, expected) in [
+            (100u64, 50u64, 150u64),
+            (200, 55, 255),
+            (200, 100, 255),
+            (255, 255, 255),
+            (0, 0, 0),
+        ] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let a = FpVar::new_witness(cs.clone(), || Ok(Fr::from(a))).unwrap();
+            let b = FpVar::new_witness(cs.clone(), || Ok(Fr::from(b))).unwrap();
+            let sum = a.saturating_add(&b, max, 8).unwrap();
+            assert_eq!(sum.value().unwrap(), Fr::from(expected));
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_saturating_add_rejects_out_of_range() {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let a = FpVar::new_witness(cs.clone(), || Ok(Fr::from(256u64))).unwrap();
+        let b = FpVar::new_witness(cs.clone(), || Ok(Fr::from(1u64))).unwrap();
+        let _ = a.saturating_add(&b, Fr::from(255u64), 8).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_checked_add_bounded() {
+        for (a, b, wrapped, overflow) in [
+            (100u64, 50u64, 150u64, false),
+            (200, 55, 255, false),
+            (200, 56, 0, true),
+            (200, 100, 44, true),
+            (255, 255, 254, true),
+            (0, 0, 0, false),
+        ] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let a = FpVar::new_witness(cs.clone(), || Ok(Fr::from(a))).unwrap();
+            let b = FpVar::new_witness(cs.clone(), || Ok(Fr::from(b))).unwrap();
+            let (sum, overflowed) = a.checked_add_bounded(&b, 8).unwrap();
+            assert_eq!(sum.value().unwrap(), Fr::from(wrapped));
+            assert_eq!(overflowed.value().unwrap(), overflow);
+            assert!(cs.is_satisfied().unwrap());
+        }
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let a = FpVar::new_witness(cs.clone(), || Ok(Fr::from(256u64))).unwrap();
+        let b = FpVar::new_witness(cs.clone(), || Ok(Fr::from(1u64))).unwrap();
+        let _ = a.checked_add_bounded(&b, 8).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_enforce_within_percent() {
+        for (value, reference, expected) in [
+            (100u64, 100u64, true),
+            (105, 100, true),
+            (95, 100, true),
+            (106, 100, false),
+            (94, 100, false),
+            (0, 0, true),
+            (1, 0, false),
+        ] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let value = FpVar::new_witness(cs.clone(), || Ok(Fr::from(value))).unwrap();
+            let reference = FpVar::new_witness(cs.clone(), || Ok(Fr::from(reference))).unwrap();
+            FpVar::enforce_within_percent(&value, &reference, 5, 16).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_enforce_clamped_noisy() {
+        let bound = Fr::from(10u64);
+        for (true_value, noisy_value, expected) in [
+            (100u64, 100u64, true),
+            (100, 110, true),
+            (100, 90, true),
+            (100, 111, false),
+            (100, 89, false),
+            (5, 0, true),
+            (0, 11, false),
+            (65535, 65525, true),
+            (65535, 65536, false),
+        ] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let true_value = FpVar::new_witness(cs.clone(), || Ok(Fr::from(true_value))).unwrap();
+            let noisy_value = FpVar::new_input(cs.clone(), || Ok(Fr::from(noisy_value))).unwrap();
+            FpVar::enforce_clamped_noisy(&true_value, &noisy_value, bound, 16).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_enforce_nonnegative_four_square() {
+        for (x, squares, expected) in [
+            // 310 = 17^2 + 4^2 + 2^2 + 1^2
+            (Fr::from(310u64), [17u64, 4, 2, 1], true),
+            (Fr::from(0u64), [0, 0, 0, 0], true),
+            (Fr::from(311u64), [17, 4, 2, 1], false),
+            // Negative values have no decomposition with bounded squares.
+            (-Fr::from(1u64), [0, 0, 0, 0], false),
+        ] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let x = FpVar::new_witness(cs.clone(), || Ok(x)).unwrap();
+            let [a, b, c, d] =
+                squares.map(|v| FpVar::new_witness(cs.clone(), || Ok(Fr::from(v))).unwrap());
+            FpVar::enforce_nonnegative_four_square(&x, &a, &b, &c, &d).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_enforce_sorted_by_key() {
+        for (keys, expected) in [
+            (&[][..], true),
+            (&[0u64, 3, 3, 8, 65535][..], true),
+            (&[0, 8, 3][..], false),
+            (&[0, 3, 65536][..], false),
+        ] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let keys = keys
+                .iter()
+                .map(|k| FpVar::new_witness(cs.clone(), || Ok(Fr::from(*k))).unwrap())
+                .collect::<Vec<_>>();
+            FpVar::enforce_sorted_by_key(&keys, 16).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_enforce_no_gaps() {
+        for (nonces, start, expected) in [
+            (&[][..], 7u64, true),
+            (&[7u64, 8, 9, 10][..], 7, true),
+            (&[252, 253, 254, 255][..], 252, true),
+            // A missing nonce.
+            (&[7, 8, 10, 11][..], 7, false),
+            // A duplicated nonce.
+            (&[7, 8, 8, 9][..], 7, false),
+            // A run that does not begin at `start`.
+            (&[8, 9, 10][..], 7, false),
+            // A run that leaves the range.
+            (&[254, 255, 256][..], 254, false),
+        ] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let nonces = nonces
+                .iter()
+                .map(|n| FpVar::new_witness(cs.clone(), || Ok(Fr::from(*n))).unwrap())
+                .collect::<Vec<_>>();
+            let start = FpVar::new_input(cs.clone(), || Ok(Fr::from(start))).unwrap();
+            FpVar::enforce_no_gaps(&nonces, &start, 8).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_enforce_value_conservation() {
+        for (inputs, outputs, fee, expected) in [
+            (&[100u64, 50][..], &[120u64, 25][..], 5u64, true),
+            (&[][..], &[][..], 0, true),
+            (&[100, 50][..], &[120, 25][..], 6, false),
+            (&[100, 50][..], &[120, 30][..], 5, false),
+        ] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let alloc = |amounts: &[u64]| {
+                amounts
+                    .iter()
+                    .map(|a| FpVar::new_witness(cs.clone(), || Ok(Fr::from(*a))).unwrap())
+                    .collect::<Vec<_>>()
+            };
+            let (inputs, outputs) = (alloc(inputs), alloc(outputs));
+            let fee = FpVar::new_input(cs.clone(), || Ok(Fr::from(fee))).unwrap();
+            FpVar::enforce_value_conservation(&inputs, &outputs, &fee, 32).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+
+        // Outputs cannot be made "negative" to balance the transaction.
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let input = FpVar::new_witness(cs.clone(), || Ok(Fr::from(10u64))).unwrap();
+        let outputs = [
+            FpVar::new_witness(cs.clone(), || Ok(Fr::from(20u64))).unwrap(),
+            FpVar::new_witness(cs.clone(), || Ok(-Fr::from(15u64))).unwrap(),
+        ];
+        let fee = FpVar::new_witness(cs.clone(), || Ok(Fr::from(5u64))).unwrap();
+        FpVar::enforce_value_conservation(&[input], &outputs, &fee, 32).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_enforce_trimmed_mean() {
+        // Sorted, this is `[1, 9, 10, 11, 12, 13, 500]`.
+        let values = [12u64, 500, 10, 1, 13, 9, 11];
+        for (values, trim, claimed_mean, expected) in [
+            (&values[..], 1, 11u64, true),
+            (&values[..], 3, 11, true),
+            (&[7][..], 0, 7, true),
+            (&[4, 4, 7, 1][..], 0, 4, true),
+            (&values[..], 1, 12, false),
+            // The untrimmed mean is `556 / 7`, which is not an integer.
+            (&values[..], 0, 79, false),
+            (&values[..], 0, 80, false),
+        ] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let values = values
+                .iter()
+                .map(|v| FpVar::new_witness(cs.clone(), || Ok(Fr::from(*v))).unwrap())
+                .collect::<Vec<_>>();
+            let claimed_mean = FpVar::new_input(cs.clone(), || Ok(Fr::from(claimed_mean))).unwrap();
+            FpVar::enforce_trimmed_mean(&values, trim, &claimed_mean, 16).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_enforce_sorted_by_key_with_payload() {
+        let mut rng = ark_std::test_rng();
+        let rows = [(5u64, 50u64), (2, 20), (9, 90), (2, 21)];
+        for (sorted, expected) in [
+            ([(2, 20), (2, 21), (5, 50), (9, 90)], true),
+            // Rows with equal keys may appear in either order.
+            ([(2, 21), (2, 20), (5, 50), (9, 90)], true),
+            // Out-of-order key.
+            ([(2, 20), (5, 50), (2, 21), (9, 90)], false),
+            // Sorted, but the payloads were swapped.
+            ([(2, 20), (2, 21), (5, 90), (9, 50)], false),
+        ] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let alloc = |rows: &[(u64, u64)]| {
+                rows.iter()
+                    .map(|(k, p)| {
+                        (
+                            FpVar::new_witness(cs.clone(), || Ok(Fr::from(*k))).unwrap(),
+                            FpVar::new_witness(cs.clone(), || Ok(Fr::from(*p))).unwrap(),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let (rows, sorted) = (alloc(&rows), alloc(&sorted));
+            let alpha = FpVar::new_input(cs.clone(), || Ok(Fr::rand(&mut rng))).unwrap();
+            let beta = FpVar::new_input(cs.clone(), || Ok(Fr::rand(&mut rng))).unwrap();
+            FpVar::enforce_sorted_by_key_with_payload(&rows, &sorted, 8, &alpha, &beta).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_limbs_round_trip() {
+        let mut rng = ark_std::test_rng();
+        let modulus_bits = Fr::MODULUS_BIT_SIZE as usize;
+        for num_bits_per_limb in [1, 8, 64, 100] {
+            let cs = ConstraintSystem::new_ref();
+            let value = Fr::rand(&mut rng);
+            let var = FpVar::new_witness(cs.clone(), || Ok(value)).unwrap();
+            let limbs = var.to_limbs(num_bits_per_limb).unwrap();
+            assert_eq!(limbs.len(), modulus_bits.div_ceil(num_bits_per_limb));
+            for limb in &limbs {
+                let limb = limb.value().unwrap().into_bigint();
+                assert!(limb.num_bits() as usize <= num_bits_per_limb);
+            }
+            let recombined = FpVar::from_limbs(&limbs, num_bits_per_limb).unwrap();
+            assert_eq!(recombined.value().unwrap(), value);
+            recombined.enforce_equal(&var).unwrap();
+            assert!(cs.is_satisfied().unwrap());
+        }
+
+        // Constant inputs produce constant limbs.
+        let limbs = FpVar::Constant(Fr::from(0x1234u64)).to_limbs(8).unwrap();
+        assert!(limbs.iter().all(|limb| limb.is_constant()));
+        assert_eq!(limbs[0].value().unwrap(), Fr::from(0x34u64));
+        assert_eq!(limbs[1].value().unwrap(), Fr::from(0x12u64));
+    }
+
+    #[test]
+    fn test_from_limbs_rejects_out_of_range() {
+        let cs = ConstraintSystem::new_ref();
+        let limbs =
+            [300u64, 1].map(|limb| FpVar::new_witness(cs.clone(), || Ok(Fr::from(limb))).unwrap());
+        let recombined = FpVar::from_limbs(&limbs, 8).unwrap();
+        assert_eq!(recombined.value().unwrap(), Fr::from(300u64 + 256));
+        assert!(!cs.is_satisfied().unwrap());
+    }
+}
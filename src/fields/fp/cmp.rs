@@ -72,6 +72,101 @@ impl<F: PrimeField> FpVar<F> {
         left.is_smaller_than_unchecked(&right)
     }
 
+    /// Enforces that `values` is sorted in ascending order, i.e. that
+    /// `values[i] < values[i + 1]` if `strict` is `true`, and that
+    /// `values[i] <= values[i + 1]` otherwise.
+    ///
+    /// This variant verifies that all values are `<= (p-1)/2`; each value is
+    /// only checked once, regardless of how many comparisons it is part of.
+    #[tracing::instrument(target = "gr1cs", skip(values))]
+    pub fn enforce_sorted(values: &[FpVar<F>], strict: bool) -> Result<(), SynthesisError> {
+        for value in values {
+            value.enforce_smaller_or_equal_than_mod_minus_one_div_two()?;
+        }
+        for pair in values.windows(2) {
+            pair[0].enforce_cmp_unchecked(&pair[1], Ordering::Less, !strict)?;
+        }
+        Ok(())
+    }
+
+    /// Outputs the bit `self < bound`, where both are interpreted as integers
+    /// in `[0, p)`.
+    ///
+    /// Unlike [`Self::is_cmp`], this does not require `self` to be
+    /// `<= (p-1)/2`. Since the bits of `bound` are known, only `self` needs to
+    /// be decomposed, and each bit of `self` is compared against the
+    /// corresponding (constant) bit of `bound`.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn is_lt_constant(&self, bound: F) -> Result<Boolean<F>, SynthesisError> {
+        if let Self::Constant(c) = self {
+            return Ok(Boolean::constant(*c < bound));
+        }
+        Ok(Boolean::le_bits_lt_constant(
+            &self.to_bits_le()?,
+            bound.into_bigint(),
+        ))
+    }
+
+    /// Outputs the bit `self <= bound`, where both are interpreted as integers
+    /// in `[0, p)`.
+    ///
+    /// See [`Self::is_lt_constant`] for details.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn is_le_constant(&self, bound: F) -> Result<Boolean<F>, SynthesisError> {
+        if bound == -F::one() {
+            // Every field element is at most `p - 1`.
+            return Ok(Boolean::TRUE);
+        }
+        self.is_lt_constant(bound + F::one())
+    }
+
+    /// Outputs the bit `self > bound`, where both are interpreted as integers
+    /// in `[0, p)`.
+    ///
+    /// See [`Self::is_lt_constant`] for details.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn is_gt_constant(&self, bound: F) -> Result<Boolean<F>, SynthesisError> {
+        Ok(!self.is_le_constant(bound)?)
+    }
+
+    /// Outputs the bit `self >= bound`, where both are interpreted as integers
+    /// in `[0, p)`.
+    ///
+    /// See [`Self::is_lt_constant`] for details.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn is_ge_constant(&self, bound: F) -> Result<Boolean<F>, SynthesisError> {
+        Ok(!self.is_lt_constant(bound)?)
+    }
+
+    /// Outputs the bit `self < other`, where both are interpreted as *signed*
+    /// integers.
+    ///
+    /// Signed integers are encoded as field elements in the usual way: an
+    /// integer `x` in `[-(p-1)/2, (p-1)/2]` is represented by `x mod p`. That
+    /// is, field elements `<= (p-1)/2` encode non-negative integers, and field
+    /// elements `> (p-1)/2` encode negative integers. Every field element is a
+    /// valid encoding, so no range checks are needed on the inputs.
+    ///
+    /// Conceptually, this compares `self + (p-1)/2` and `other + (p-1)/2` as
+    /// unsigned integers. Concretely, the sign of each input is extracted, and
+    /// inputs with the same sign are compared after shifting the negative
+    /// ones into `[0, (p-1)/2)`.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn is_lt_signed(&self, other: &FpVar<F>) -> Result<Boolean<F>, SynthesisError> {
+        let half: F = F::MODULUS_MINUS_ONE_DIV_TWO.into();
+        let self_is_neg = self.is_gt_constant(half)?;
+        let other_is_neg = other.is_gt_constant(half)?;
+        // Shifting negative values by `-(p+1)/2` maps `[(p+1)/2, p)` onto
+        // `[0, (p-1)/2)` while preserving their order, so both shifted
+        // values are `<= (p-1)/2`.
+        let shift = half + F::one();
+        let self_shifted = self - Self::from(self_is_neg.clone()) * shift;
+        let other_shifted = other - Self::from(other_is_neg.clone()) * shift;
+        let same_sign_lt = self_shifted.is_smaller_than_unchecked(&other_shifted)?;
+        // If the signs differ, `self < other` iff `self` is the negative one.
+        (&self_is_neg ^ &other_is_neg).select(&self_is_neg, &same_sign_lt)
+    }
+
     fn process_cmp_inputs(
         &self,
         other: &Self,
@@ -147,8 +242,8 @@ impl<F: PrimeField> FpVar<F> {
 mod test {
     use ark_std::{cmp::Ordering, rand::Rng};
 
-    use crate::{alloc::AllocVar, fields::fp::FpVar};
-    use ark_ff::{PrimeField, UniformRand};
+    use crate::{alloc::AllocVar, fields::fp::FpVar, GR1CSVar};
+    use ark_ff::{One, PrimeField, UniformRand, Zero};
     use ark_relations::gr1cs::ConstraintSystem;
     use ark_test_curves::bls12_381::Fr;
 
@@ -231,4 +326,95 @@ mod test {
             assert!(cs.is_satisfied().unwrap());
         }
     }
+
+    fn check_cmp_constant(a: Fr, bound: Fr) {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let a_var = FpVar::new_witness(cs.clone(), || Ok(a)).unwrap();
+        for a_var in [a_var, FpVar::Constant(a)] {
+            let lt = a_var.is_lt_constant(bound).unwrap();
+            let le = a_var.is_le_constant(bound).unwrap();
+            let gt = a_var.is_gt_constant(bound).unwrap();
+            let ge = a_var.is_ge_constant(bound).unwrap();
+            assert_eq!(lt.value().unwrap(), a < bound);
+            assert_eq!(le.value().unwrap(), a <= bound);
+            assert_eq!(gt.value().unwrap(), a > bound);
+            assert_eq!(ge.value().unwrap(), a >= bound);
+        }
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_cmp_constant() {
+        let mut rng = ark_std::test_rng();
+        for _ in 0..10 {
+            let a = Fr::rand(&mut rng);
+            let bound = Fr::rand(&mut rng);
+            check_cmp_constant(a, bound);
+            // Values around the boundary.
+            check_cmp_constant(bound - Fr::one(), bound);
+            check_cmp_constant(bound, bound);
+            check_cmp_constant(bound + Fr::one(), bound);
+        }
+        for bound in [Fr::zero(), Fr::one(), -Fr::one()] {
+            for a in [Fr::zero(), Fr::one(), -Fr::one(), Fr::rand(&mut rng)] {
+                check_cmp_constant(a, bound);
+            }
+        }
+    }
+
+    #[test]
+    fn test_is_lt_signed() {
+        let half: Fr = Fr::MODULUS_MINUS_ONE_DIV_TWO.into();
+        // Encodings of signed integers, in increasing order.
+        let values = [
+            -half,
+            -Fr::from(1000u64),
+            -Fr::one(),
+            Fr::zero(),
+            Fr::one(),
+            Fr::from(1000u64),
+            half,
+        ];
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        for (i, a) in values.iter().enumerate() {
+            for (j, b) in values.iter().enumerate() {
+                let a_var = FpVar::new_witness(cs.clone(), || Ok(*a)).unwrap();
+                let b_var = FpVar::new_witness(cs.clone(), || Ok(*b)).unwrap();
+                let lt = a_var.is_lt_signed(&b_var).unwrap();
+                assert_eq!(lt.value().unwrap(), i < j, "{i} < {j}");
+            }
+        }
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_enforce_sorted() {
+        for (values, strict, expected) in [
+            (&[][..], false, true),
+            (&[][..], true, true),
+            (&[7u64][..], false, true),
+            (&[7][..], true, true),
+            (&[1, 4, 9, 16, 25][..], false, true),
+            (&[1, 4, 9, 16, 25][..], true, true),
+            (&[1, 4, 16, 9, 25][..], false, false),
+            (&[1, 4, 16, 9, 25][..], true, false),
+            (&[1, 4, 4, 9, 9][..], false, true),
+            (&[1, 4, 4, 9, 9][..], true, false),
+        ] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let values = values
+                .iter()
+                .map(|v| FpVar::new_witness(cs.clone(), || Ok(Fr::from(*v))).unwrap())
+                .collect::<Vec<_>>();
+            FpVar::enforce_sorted(&values, strict).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+
+        // Values above `(p-1)/2` are rejected.
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let values =
+            [Fr::one(), -Fr::one()].map(|v| FpVar::new_witness(cs.clone(), || Ok(v)).unwrap());
+        FpVar::enforce_sorted(&values, true).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
 }
@@ -0,0 +1,305 @@
+use crate::{fields::fp::FpVar, prelude::*, Vec};
+use ark_ff::PrimeField;
+use ark_relations::gr1cs::SynthesisError;
+
+impl<F: PrimeField> FpVar<F> {
+    /// Enforces that `commitment_eval` is the evaluation of the polynomial
+    /// committing to `value` at index `position` in Lagrange form, i.e. that
+    /// `commitment_eval == value * lagrange_evals[position]`.
+    ///
+    /// Here, `lagrange_evals[i]` is the evaluation of the `i`-th Lagrange basis
+    /// polynomial of the commitment domain at the evaluation point.
+    ///
+    /// The constraint system will not be satisfied if `position` is not in
+    /// `[0, lagrange_evals.len())`.
+    #[tracing::instrument(target = "gr1cs", skip(lagrange_evals))]
+    pub fn enforce_vector_position(
+        commitment_eval: &Self,
+        position: &Self,
+        value: &Self,
+        lagrange_evals: &[Self],
+    ) -> Result<(), SynthesisError> {
+        let indices = (0..lagrange_evals.len() as u64)
+            .map(F::from)
+            .collect::<Vec<_>>();
+        let indicators = position.equality_indicators(&indices)?;
+        let num_matches: Self = indicators.iter().cloned().map(Self::from).sum();
+        num_matches.enforce_equal(&Self::one())?;
+
+        let indicators = indicators.into_iter().map(Self::from).collect::<Vec<_>>();
+        let basis_eval = Self::inner_product(&indicators, lagrange_evals)?;
+        value.mul_equals(&basis_eval, commitment_eval)
+    }
+
+    /// Enforces that `claimed_eval` is the evaluation at `point` of the
+    /// polynomial whose coefficients are given by the sparse vector `pairs`,
+    /// i.e. that `claimed_eval == sum_k value_k * point^index_k`, where
+    /// `pairs[k] = (index_k, value_k)`.
+    ///
+    /// This method enforces that each index is smaller than
+    /// `2^index_bit_size`, and computes `point^index_k` from the bits of
+    /// `index_k` via [`FieldVar::pow_le`].
+    #[tracing::instrument(target = "gr1cs", skip(pairs))]
+    pub fn enforce_sparse_vector_eval(
+        pairs: &[(Self, Self)],
+        point: &Self,
+        claimed_eval: &Self,
+        index_bit_size: usize,
+    ) -> Result<(), SynthesisError> {
+        let mut powers = Vec::with_capacity(pairs.len());
+        for (index, _) in pairs {
+            let (bits, _) = index.to_bits_le_with_top_bits_zero(index_bit_size)?;
+            powers.push(point.pow_le(&bits)?);
+        }
+        let values = pairs
+            .iter()
+            .map(|(_, value)| value.clone())
+            .collect::<Vec<_>>();
+        Self::inner_product(&values, &powers)?.enforce_equal(claimed_eval)
+    }
+
+    /// Enforces that `evals[i]` is the evaluation at `domain[i]` of the
+    /// polynomial with coefficients `coeffs`, i.e. that
+    /// `evals[i] == sum_j coeffs[j] * domain[i]^j` for every `i`.
+    ///
+    /// Since the domain is constant, the powers of each point are computed
+    /// natively, and each evaluation is a linear combination of `coeffs`
+    /// computed via [`Self::sparse_weighted_sum`]. This thus requires one
+    /// constraint per domain point, and does not require the domain to be a
+    /// multiplicative subgroup.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `evals` and `domain` have different lengths.
+    #[tracing::instrument(target = "gr1cs", skip(coeffs, evals, domain))]
+    pub fn enforce_coeff_eval_consistency(
+        coeffs: &[Self],
+        evals: &[Self],
+        domain: &[F],
+    ) -> Result<(), SynthesisError> {
+        assert_eq!(evals.len(), domain.len());
+        for (eval, point) in evals.iter().zip(domain) {
+            let powers = ark_std::iter::successors(Some(F::one()), |p| Some(*p * point))
+                .take(coeffs.len())
+                .collect::<Vec<_>>();
+            Self::sparse_weighted_sum(&powers, coeffs)?.enforce_equal(eval)?;
+        }
+        Ok(())
+    }
+
+    /// Enforces that `evals` are the evaluations at `0, 1, ..., n - 1` of a
+    /// polynomial `p` of degree at most `claimed_degree`, and that
+    /// `p(point) == claimed_eval` for every `(point, claimed_eval)` in
+    /// `challenge_points`.
+    ///
+    /// `p` is the interpolant of the first `claimed_degree + 1` evaluations.
+    /// Each remaining evaluation is checked against `p` via constant Lagrange
+    /// coefficients, which requires one constraint per evaluation. At each
+    /// challenge point, `p` is evaluated via the Lagrange basis, which
+    /// requires at most `4 * (claimed_degree + 1)` constraints.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `claimed_degree >= evals.len()`.
+    #[tracing::instrument(target = "gr1cs", skip(evals, challenge_points))]
+    pub fn enforce_low_degree_spotcheck(
+        evals: &[Self],
+        claimed_degree: usize,
+        challenge_points: &[(Self, Self)],
+    ) -> Result<(), SynthesisError> {
+        assert!(claimed_degree < evals.len());
+        let (interpolated, rest) = evals.split_at(claimed_degree + 1);
+        let nodes = (0..interpolated.len() as u64)
+            .map(F::from)
+            .collect::<Vec<_>>();
+        // `weights[i] = 1 / prod_{j != i} (i - j)`, so that the `i`-th
+        // Lagrange basis polynomial is `weights[i] * prod_{j != i} (x - j)`.
+        let weights = nodes
+            .iter()
+            .map(|i| {
+                let denominator = nodes
+                    .iter()
+                    .filter(|j| *j != i)
+                    .map(|j| *i - j)
+                    .product::<F>();
+                denominator.inverse().unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        for (k, eval) in rest.iter().enumerate() {
+            let x = F::from((interpolated.len() + k) as u64);
+            let differences = nodes.iter().map(|j| x - j).collect::<Vec<_>>();
+            let coeffs = weights
+                .iter()
+                .enumerate()
+                .map(|(i, w)| {
+                    let others = differences.iter().enumerate().filter(|(j, _)| *j != i);
+                    *w * others.map(|(_, d)| *d).product::<F>()
+                })
+                .collect::<Vec<_>>();
+            Self::sparse_weighted_sum(&coeffs, interpolated)?.enforce_equal(eval)?;
+        }
+
+        for (point, claimed_eval) in challenge_points {
+            let differences = nodes.iter().map(|j| point - *j).collect::<Vec<_>>();
+            // `prefix[i]` and `suffix[i]` are the products of the differences
+            // before and from index `i`, respectively.
+            let mut prefix = vec![Self::one()];
+            for d in &differences {
+                prefix.push(prefix.last().unwrap() * d);
+            }
+            let mut suffix = vec![Self::one()];
+            for d in differences.iter().rev() {
+                suffix.push(suffix.last().unwrap() * d);
+            }
+            suffix.reverse();
+            let mut eval = Self::zero();
+            for (i, (value, weight)) in interpolated.iter().zip(&weights).enumerate() {
+                let basis = &prefix[i] * &suffix[i + 1];
+                eval += basis * value * *weight;
+            }
+            eval.enforce_equal(claimed_eval)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{alloc::AllocVar, fields::fp::FpVar};
+    use ark_ff::Field;
+    use ark_relations::gr1cs::ConstraintSystem;
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::Fr;
+
+    #[test]
+    fn test_enforce_vector_position() {
+        let mut rng = ark_std::test_rng();
+        let value = Fr::rand(&mut rng);
+        let lagrange_evals = (0..8).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        for (position, claimed_value, expected) in [
+            (0u64, value, true),
+            (5, value, true),
+            (5, value + Fr::from(1u64), false),
+        ] {
+            let commitment_eval = value * lagrange_evals[position as usize];
+            let cs = ConstraintSystem::new_ref();
+            let commitment_eval = FpVar::new_witness(cs.clone(), || Ok(commitment_eval)).unwrap();
+            let position = FpVar::new_witness(cs.clone(), || Ok(Fr::from(position))).unwrap();
+            let claimed_value = FpVar::new_witness(cs.clone(), || Ok(claimed_value)).unwrap();
+            let lagrange_evals = lagrange_evals
+                .iter()
+                .map(|e| FpVar::new_input(cs.clone(), || Ok(*e)).unwrap())
+                .collect::<Vec<_>>();
+            FpVar::enforce_vector_position(
+                &commitment_eval,
+                &position,
+                &claimed_value,
+                &lagrange_evals,
+            )
+            .unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_enforce_sparse_vector_eval() {
+        let mut rng = ark_std::test_rng();
+        let point = Fr::rand(&mut rng);
+        let pairs = [
+            (0u64, Fr::from(7u64)),
+            (13, Fr::from(2u64)),
+            (1000, -Fr::from(5u64)),
+        ];
+        let eval = pairs
+            .iter()
+            .map(|(index, value)| *value * point.pow([*index]))
+            .sum::<Fr>();
+        for (pairs, claimed_eval, expected) in [
+            (&pairs[..], eval, true),
+            (&pairs[..], eval + Fr::from(1u64), false),
+            // Indices must fit in 10 bits: the low 10 bits of 1024 are zero,
+            // so this would otherwise evaluate to `3 * point^0`.
+            (&[(1024, Fr::from(3u64))][..], Fr::from(3u64), false),
+        ] {
+            let cs = ConstraintSystem::new_ref();
+            let pairs = pairs
+                .iter()
+                .map(|(index, value)| {
+                    (
+                        FpVar::new_witness(cs.clone(), || Ok(Fr::from(*index))).unwrap(),
+                        FpVar::new_witness(cs.clone(), || Ok(*value)).unwrap(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            let point = FpVar::new_input(cs.clone(), || Ok(point)).unwrap();
+            let claimed_eval = FpVar::new_witness(cs.clone(), || Ok(claimed_eval)).unwrap();
+            FpVar::enforce_sparse_vector_eval(&pairs, &point, &claimed_eval, 10).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_enforce_coeff_eval_consistency() {
+        let mut rng = ark_std::test_rng();
+        let coeffs = (0..5).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let domain = (0..7).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        // Evaluate natively via Horner's rule.
+        let evals = domain
+            .iter()
+            .map(|x| {
+                coeffs
+                    .iter()
+                    .rev()
+                    .fold(Fr::from(0u64), |acc, c| acc * x + c)
+            })
+            .collect::<Vec<_>>();
+        let mut wrong = evals.clone();
+        wrong[3] += Fr::from(1u64);
+
+        for (evals, expected) in [(evals, true), (wrong, false)] {
+            let cs = ConstraintSystem::new_ref();
+            let coeffs = coeffs
+                .iter()
+                .map(|c| FpVar::new_witness(cs.clone(), || Ok(*c)).unwrap())
+                .collect::<Vec<_>>();
+            let evals = evals
+                .iter()
+                .map(|e| FpVar::new_input(cs.clone(), || Ok(*e)).unwrap())
+                .collect::<Vec<_>>();
+            FpVar::enforce_coeff_eval_consistency(&coeffs, &evals, &domain).unwrap();
+            assert_eq!(cs.num_constraints(), domain.len());
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_enforce_low_degree_spotcheck() {
+        let mut rng = ark_std::test_rng();
+        let point = Fr::rand(&mut rng);
+        let quadratic = (0..3).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let cubic = (0..4).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        for (poly, num_evals, degree, expected) in [
+            (&quadratic, 3u64, 2, true),
+            (&quadratic, 8, 2, true),
+            (&cubic, 8, 3, true),
+            // The extra evaluations of the cubic are inconsistent with the
+            // interpolant of the first three.
+            (&cubic, 8, 2, false),
+            // Without extra evaluations, the spot check catches the cubic.
+            (&cubic, 3, 2, false),
+        ] {
+            let eval = |x: Fr| poly.iter().rev().fold(Fr::from(0u64), |acc, c| acc * x + c);
+            let cs = ConstraintSystem::new_ref();
+            let evals = (0..num_evals)
+                .map(|i| FpVar::new_witness(cs.clone(), || Ok(eval(Fr::from(i)))).unwrap())
+                .collect::<Vec<_>>();
+            let challenge = (
+                FpVar::new_input(cs.clone(), || Ok(point)).unwrap(),
+                FpVar::new_witness(cs.clone(), || Ok(eval(point))).unwrap(),
+            );
+            FpVar::enforce_low_degree_spotcheck(&evals, degree, &[challenge]).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+}
@@ -0,0 +1,628 @@
+use crate::{
+    fields::fp::{power_of_two, FpVar},
+    prelude::*,
+    Vec,
+};
+use ark_ff::PrimeField;
+use ark_relations::gr1cs::SynthesisError;
+use num_bigint::BigUint;
+
+/// A piecewise-polynomial function on signed fixed-point numbers with
+/// `frac_bits` fractional bits.
+///
+/// The domain `[start, start + 2^(index_bits + segment_bits))` (in units of
+/// `2^-frac_bits`) is split into `2^index_bits` segments of width
+/// `2^segment_bits`, where `2^index_bits == coefficients.len()`. On the `j`-th
+/// segment, the function is the polynomial with fixed-point coefficients
+/// `coefficients[j]` (constant term first), evaluated at the offset of the
+/// input from the start of the segment.
+#[derive(Clone, Debug)]
+pub struct PiecewisePolyConfig<F: PrimeField> {
+    /// The number of fractional bits of the inputs, outputs and coefficients.
+    pub frac_bits: usize,
+    /// The start of the domain, as a signed fixed-point number.
+    pub start: F,
+    /// The base-2 logarithm of the width of each segment.
+    pub segment_bits: usize,
+    /// The coefficients of the polynomial on each segment. All segments must
+    /// have the same number of coefficients, and the number of segments must
+    /// be a power of two.
+    pub coefficients: Vec<Vec<F>>,
+}
+
+impl<F: PrimeField> FpVar<F> {
+    /// Enforces that `self`, interpreted as a signed integer, lies in
+    /// `[-2^(bit_size - 1), 2^(bit_size - 1))`.
+    ///
+    /// Negative integers `-x` are encoded as the field element `p - x`.
+    ///
+    /// This requires `bit_size + 1` constraints.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn enforce_signed_bit_size(&self, bit_size: usize) -> Result<(), SynthesisError> {
+        assert!(bit_size > 0);
+        (self + power_of_two::<F>(bit_size as u32 - 1)).enforce_bit_size(bit_size)
+    }
+
+    /// Outputs `self / 2^frac_bits`, rounded to the nearest integer (with ties
+    /// rounded up), where `self` is interpreted as a signed integer in
+    /// `[-2^(bit_size - 1), 2^(bit_size - 1))`.
+    ///
+    /// This function assumes that `self` lies in that range. The output is
+    /// a signed integer in `[-2^(bit_size - 1 - frac_bits), 2^(bit_size - 1 -
+    /// frac_bits)]`.
+    fn rescale_signed(&self, frac_bits: usize, bit_size: usize) -> Result<Self, SynthesisError> {
+        assert!(frac_bits < bit_size);
+        // Shift `self` into the non-negative range, and add half of the
+        // divisor to round to the nearest integer. The result is smaller than
+        // `2^(bit_size + 1)`.
+        let mut offset = power_of_two::<F>(bit_size as u32 - 1);
+        if frac_bits > 0 {
+            offset += power_of_two::<F>(frac_bits as u32 - 1);
+        }
+        let shifted = self + offset;
+        let (bits, _) = shifted.to_bits_le_with_top_bits_zero(bit_size + 1)?;
+        // Dropping the low bits divides by `2^frac_bits`; then undo the shift.
+        let quotient = Boolean::le_bits_to_fp(&bits[frac_bits..])?;
+        Ok(quotient - power_of_two::<F>((bit_size - 1 - frac_bits) as u32))
+    }
+
+    /// Outputs `max(0, self)`, where `self` is interpreted as a signed
+    /// integer in `[-2^(n - 1), 2^(n - 1))`.
+    ///
+    /// The sign of `self` is read off the `n`-bit decomposition of
+    /// `self + 2^(n - 1)`, which also enforces that `self` is in range.
+    ///
+    /// This requires `n + 2` constraints.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn relu_signed(&self, n: usize) -> Result<Self, SynthesisError> {
+        assert!(n > 0);
+        let shifted = self + power_of_two::<F>(n as u32 - 1);
+        let (bits, _) = shifted.to_bits_le_with_top_bits_zero(n)?;
+        // `self >= 0` exactly when `shifted >= 2^(n - 1)`.
+        let is_nonnegative = &bits[n - 1];
+        is_nonnegative.select(self, &Self::zero())
+    }
+
+    /// Outputs the maximum of `window`, where the entries are interpreted as
+    /// signed integers in `[-2^(bit_size - 1), 2^(bit_size - 1))`.
+    ///
+    /// This function assumes that the entries lie in that range, and does
+    /// not generate constraints to verify that; use
+    /// [`Self::enforce_signed_bit_size`] to check this.
+    ///
+    /// This requires `bit_size + 3` constraints per entry after the first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is empty.
+    #[tracing::instrument(target = "gr1cs", skip(window))]
+    pub fn max_pool(window: &[Self], bit_size: usize) -> Result<Self, SynthesisError> {
+        assert!(!window.is_empty());
+        let offset = power_of_two::<F>(bit_size as u32 - 1);
+        let mut max = window[0].clone();
+        for value in &window[1..] {
+            let is_larger = (&max + offset).is_lt_bounded(&(value + offset), bit_size)?;
+            max = is_larger.select(value, &max)?;
+        }
+        Ok(max)
+    }
+
+    /// Evaluates the piecewise polynomial described by `config` at `self`,
+    /// where `self` is interpreted as a signed fixed-point number.
+    ///
+    /// The segment is selected from the bit decomposition of
+    /// `self - config.start`, which also enforces that `self` lies in the
+    /// domain of `config`. The polynomial is evaluated via Horner's rule,
+    /// rounding to the nearest representable value after each
+    /// multiplication, so a polynomial with `d + 1` coefficients incurs an
+    /// evaluation error of at most `d / 2` units in the last place (on top of
+    /// the error of the approximation itself).
+    ///
+    /// This function assumes that all intermediate Horner values fit in
+    /// `bit_size` signed bits, and the constraint system is unsatisfiable if
+    /// that is not the case.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `config` is malformed, or if `2 * bit_size + 1` is not
+    /// smaller than the size of the field modulus.
+    #[tracing::instrument(target = "gr1cs", skip(config))]
+    pub fn piecewise_poly(
+        &self,
+        config: &PiecewisePolyConfig<F>,
+        bit_size: usize,
+    ) -> Result<Self, SynthesisError> {
+        let num_segments = config.coefficients.len();
+        assert!(num_segments.is_power_of_two());
+        let num_coeffs = config.coefficients[0].len();
+        assert!(num_coeffs > 0);
+        assert!(config.coefficients.iter().all(|c| c.len() == num_coeffs));
+        let index_bits = num_segments.trailing_zeros() as usize;
+
+        let offset = self - config.start;
+        let (bits, _) = offset.to_bits_le_with_top_bits_zero(config.segment_bits + index_bits)?;
+        let (local_bits, selector) = bits.split_at(config.segment_bits);
+        let local = Boolean::le_bits_to_fp(local_bits)?;
+
+        let coefficient = |k: usize| {
+            let column = config.coefficients.iter().map(|c| c[k]).collect::<Vec<_>>();
+            Self::select_constant(selector, &column)
+        };
+        let mut acc = coefficient(num_coeffs - 1)?;
+        for k in (0..num_coeffs - 1).rev() {
+            acc = (acc * &local).rescale_signed(config.frac_bits, 2 * bit_size)? + coefficient(k)?;
+        }
+        Ok(acc)
+    }
+
+    /// Outputs `table[i]`, where `i` is the integer with little-endian bits
+    /// `bits`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `table.len() != 2^bits.len()`.
+    fn select_constant(bits: &[Boolean<F>], table: &[F]) -> Result<Self, SynthesisError> {
+        assert_eq!(table.len(), 1 << bits.len());
+        match bits.split_last() {
+            None => Ok(Self::constant(table[0])),
+            Some((top, rest)) => {
+                let (low, high) = table.split_at(table.len() / 2);
+                let low = Self::select_constant(rest, low)?;
+                let high = Self::select_constant(rest, high)?;
+                top.select(&high, &low)
+            },
+        }
+    }
+
+    /// Outputs an approximation of `exp(self)`, where `self` is interpreted as
+    /// a signed fixed-point number, by evaluating the precomputed
+    /// piecewise-polynomial approximation `segments` via
+    /// [`Self::piecewise_poly`].
+    ///
+    /// The approximation error is the error of `segments` itself (e.g. the
+    /// Taylor remainder `e^(a + h) h^(d + 1) / (d + 1)!` for a degree-`d`
+    /// expansion around the start `a` of a segment of width `h`), plus the
+    /// rounding of the coefficients and at most `d / 2` units in the last
+    /// place from the evaluation.
+    ///
+    /// `self` must lie in the domain of `segments`, and all intermediate
+    /// values must fit in `bit_size` signed bits; otherwise, the constraint
+    /// system is unsatisfiable.
+    #[tracing::instrument(target = "gr1cs", skip(segments))]
+    pub fn exp_approx(
+        &self,
+        segments: &PiecewisePolyConfig<F>,
+        bit_size: usize,
+    ) -> Result<Self, SynthesisError> {
+        self.piecewise_poly(segments, bit_size)
+    }
+
+    /// Outputs `self / divisor`, rounded to the nearest integer (with ties
+    /// rounded up), where `self` is interpreted as a signed integer in
+    /// `[-2^(bit_size - 1), 2^(bit_size - 1))` and `divisor` as a positive
+    /// integer.
+    ///
+    /// This function assumes that `self` lies in that range. The output is
+    /// a signed integer in `[-2^(bit_size - 1), 2^(bit_size - 1)]`.
+    fn div_round_signed(&self, divisor: F, bit_size: usize) -> Result<Self, SynthesisError> {
+        let d: BigUint = divisor.into();
+        let divisor_bits = d.bits() as usize;
+        assert!(divisor_bits > 0);
+        assert!(bit_size + divisor_bits + 1 < F::MODULUS_BIT_SIZE as usize);
+        // Shift `self` into the non-negative range by a multiple of the
+        // divisor, and add half of the divisor to round to the nearest
+        // integer. The result is smaller than `2^bit_size + 2 * divisor`.
+        let k = F::from(((BigUint::from(1u8) << (bit_size - 1)) + &d - 1u8) / &d);
+        let shifted = self + (k * divisor + F::from(&d >> 1));
+
+        let cs = self.cs();
+        let mode = if cs.is_none() {
+            AllocationMode::Constant
+        } else {
+            AllocationMode::Witness
+        };
+        let shifted_value = || -> Result<BigUint, SynthesisError> { Ok(shifted.value()?.into()) };
+        let quotient = Self::new_variable(cs.clone(), || Ok(F::from(shifted_value()? / &d)), mode)?;
+        let remainder = Self::new_variable(cs, || Ok(F::from(shifted_value()? % &d)), mode)?;
+
+        // Since `quotient < 2^(bit_size + 1)` and `remainder < divisor`, the
+        // following equation cannot overflow, so it holds over the integers.
+        remainder.enforce_bit_size(divisor_bits)?;
+        (Self::constant(divisor - F::one()) - &remainder).enforce_bit_size(divisor_bits)?;
+        quotient.enforce_bit_size(bit_size + 1)?;
+        (&quotient * divisor + &remainder).enforce_equal(&shifted)?;
+        Ok(quotient - k)
+    }
+
+    /// Outputs `self` clamped to `[min, max]`, where `self`, `min` and `max`
+    /// are interpreted as signed integers in
+    /// `[-2^(bit_size - 1), 2^(bit_size - 1))`.
+    ///
+    /// This function assumes that `self` lies in that range.
+    fn clamp_signed(&self, min: F, max: F, bit_size: usize) -> Result<Self, SynthesisError> {
+        let offset = power_of_two::<F>(bit_size as u32 - 1);
+        let shifted = self + offset;
+        let below = shifted.is_lt_bounded(&Self::constant(min + offset), bit_size)?;
+        let above = Self::constant(max + offset).is_lt_bounded(&shifted, bit_size)?;
+        let upper_clamped = above.select(&Self::constant(max), self)?;
+        below.select(&Self::constant(min), &upper_clamped)
+    }
+
+    /// Outputs `clamp(round(self / scale) + zero_point, qmin, qmax)`, where
+    /// the division rounds to the nearest integer (with ties rounded up).
+    ///
+    /// Here, `self` is interpreted as a signed integer in
+    /// `[-2^(bit_size - 1), 2^(bit_size - 1))`, `scale` as a positive integer,
+    /// and `zero_point`, `qmin` and `qmax` as signed integers in the same
+    /// range as `self`.
+    ///
+    /// This method assumes that `self` is in range; use
+    /// [`Self::enforce_signed_bit_size`] to check this.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scale` is zero, or if `bit_size` plus the bit size of
+    /// `scale` is too close to the size of the field modulus.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn quantize(
+        &self,
+        scale: F,
+        zero_point: F,
+        qmin: F,
+        qmax: F,
+        bit_size: usize,
+    ) -> Result<Self, SynthesisError> {
+        let rounded = self.div_round_signed(scale, bit_size)?;
+        // Both `rounded` and `zero_point` fit in `bit_size` signed bits (up to
+        // the single value `2^(bit_size - 1)`), so their sum fits in
+        // `bit_size + 1` signed bits.
+        (rounded + zero_point).clamp_signed(qmin, qmax, bit_size + 1)
+    }
+
+    /// Outputs `(self - zero_point) * scale`, which reverses [`Self::quantize`]
+    /// up to rounding and clamping.
+    ///
+    /// Since `scale` and `zero_point` are constants, this is a linear
+    /// combination of `self` and requires no constraints.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn dequantize(&self, scale: F, zero_point: F) -> Result<Self, SynthesisError> {
+        Ok((self - zero_point) * scale)
+    }
+
+    /// Outputs `acc + a * b`, where all values are signed fixed-point numbers
+    /// with `frac_bits` fractional bits, whose underlying integers lie in
+    /// `[-2^(bit_size - 1), 2^(bit_size - 1))`.
+    ///
+    /// The full-precision product is added to the (scaled) accumulator
+    /// before rescaling, so only a single rounding to the nearest
+    /// representable value takes place.
+    ///
+    /// This method assumes that `acc`, `a` and `b` are in range, and enforces
+    /// that the output is in range. Chaining calls therefore only requires the
+    /// initial inputs to be range-checked.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn signed_fixed_mac(
+        acc: &Self,
+        a: &Self,
+        b: &Self,
+        frac_bits: usize,
+        bit_size: usize,
+    ) -> Result<Self, SynthesisError> {
+        assert!(frac_bits < bit_size);
+        let scaled_acc = acc.scale_by_power_of_two(frac_bits as u32)?;
+        let full_precision = scaled_acc + a * b;
+        // `|a * b| <= 2^(2 * bit_size - 2)` and `|acc * 2^frac_bits| <=
+        // 2^(bit_size - 1 + frac_bits)`, so the sum fits in this many signed
+        // bits.
+        let full_precision_bits = ark_std::cmp::max(2 * bit_size - 2, bit_size - 1 + frac_bits) + 2;
+        let result = full_precision.rescale_signed(frac_bits, full_precision_bits)?;
+        result.enforce_signed_bit_size(bit_size)?;
+        Ok(result)
+    }
+
+    /// Outputs the inner product of `a` and `b`, where all entries are signed
+    /// fixed-point numbers with `frac_bits` fractional bits, whose underlying
+    /// integers lie in `[-2^(bit_size - 1), 2^(bit_size - 1))`.
+    ///
+    /// The products are accumulated at full precision, and the result is
+    /// rescaled (and rounded to the nearest representable value) only once.
+    /// This is much cheaper than calling [`Self::signed_fixed_mac`] for each
+    /// entry.
+    ///
+    /// This method assumes that the entries of `a` and `b` are in range, and
+    /// enforces that the output is in range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` and `b` have different lengths.
+    #[tracing::instrument(target = "gr1cs", skip(a, b))]
+    pub fn fixed_dot_product(
+        a: &[Self],
+        b: &[Self],
+        frac_bits: usize,
+        bit_size: usize,
+    ) -> Result<Self, SynthesisError> {
+        assert_eq!(a.len(), b.len());
+        assert!(frac_bits < bit_size);
+        let full_precision = Self::inner_product(a, b)?;
+        // Each product is at most `2^(2 * bit_size - 2)` in absolute value.
+        let len_bits = (usize::BITS - a.len().leading_zeros()) as usize;
+        let full_precision_bits = 2 * bit_size - 1 + len_bits;
+        let result = full_precision.rescale_signed(frac_bits, full_precision_bits)?;
+        result.enforce_signed_bit_size(bit_size)?;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        alloc::AllocVar,
+        fields::{
+            fp::{FpVar, PiecewisePolyConfig},
+            FieldVar,
+        },
+        GR1CSVar,
+    };
+    use ark_relations::gr1cs::ConstraintSystem;
+    use ark_std::rand::Rng;
+    use ark_test_curves::bls12_381::Fr;
+
+    const FRAC_BITS: usize = 8;
+    const BIT_SIZE: usize = 24;
+
+    fn to_field(x: i64) -> Fr {
+        if x < 0 {
+            -Fr::from(x.unsigned_abs())
+        } else {
+            Fr::from(x as u64)
+        }
+    }
+
+    /// Native counterpart of `signed_fixed_mac`.
+    fn native_mac(acc: i64, a: i64, b: i64) -> i64 {
+        let full_precision = (acc << FRAC_BITS) + a * b;
+        (full_precision + (1 << (FRAC_BITS - 1))).div_euclid(1 << FRAC_BITS)
+    }
+
+    #[test]
+    fn test_relu_signed() {
+        let n = 8;
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        for x in -(1i64 << (n - 1))..(1 << (n - 1)) {
+            let x_var = FpVar::new_witness(cs.clone(), || Ok(to_field(x))).unwrap();
+            let relu = x_var.relu_signed(n).unwrap();
+            assert_eq!(relu.value().unwrap(), to_field(x.max(0)));
+        }
+        assert!(cs.is_satisfied().unwrap());
+
+        let relu = FpVar::constant(to_field(-5)).relu_signed(n).unwrap();
+        assert_eq!(relu.value().unwrap(), to_field(0));
+
+        // Out-of-range inputs are rejected.
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let x_var = FpVar::new_witness(cs.clone(), || Ok(to_field(1 << (n - 1)))).unwrap();
+        let _ = x_var.relu_signed(n).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_max_pool() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let mut windows = (0..16)
+            .map(|_| {
+                (0..4)
+                    .map(|_| rng.gen_range(-128i64..128))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        windows.push(vec![-3, -128, -7, -1]);
+        windows.push(vec![127, -128]);
+        windows.push(vec![-42]);
+        for window in windows {
+            let window_vars = window
+                .iter()
+                .map(|x| FpVar::new_witness(cs.clone(), || Ok(to_field(*x))).unwrap())
+                .collect::<Vec<_>>();
+            let max = FpVar::max_pool(&window_vars, n).unwrap();
+            let expected = *window.iter().max().unwrap();
+            assert_eq!(max.value().unwrap(), to_field(expected));
+        }
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    /// Builds a degree-4 Taylor approximation of `exp` on `[-4, 4)`, split
+    /// into 16 segments of width 1/2.
+    fn exp_config() -> PiecewisePolyConfig<Fr> {
+        let segment_bits = FRAC_BITS - 1;
+        let start = -4i64 << FRAC_BITS;
+        let coefficients = (0..16)
+            .map(|j| {
+                let a = (start + (j << segment_bits)) as f64 / (1 << FRAC_BITS) as f64;
+                let mut factorial = 1.0;
+                (0..5)
+                    .map(|k| {
+                        if k > 0 {
+                            factorial *= k as f64;
+                        }
+                        let c = a.exp() / factorial;
+                        to_field((c * (1 << FRAC_BITS) as f64).round() as i64)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        PiecewisePolyConfig {
+            frac_bits: FRAC_BITS,
+            start: to_field(start),
+            segment_bits,
+            coefficients,
+        }
+    }
+
+    #[test]
+    fn test_exp_approx() {
+        let mut rng = ark_std::test_rng();
+        let config = exp_config();
+        let one = (1 << FRAC_BITS) as f64;
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let mut inputs = (0..32)
+            .map(|_| rng.gen_range(-4 * (1 << FRAC_BITS)..4 * (1 << FRAC_BITS)))
+            .collect::<Vec<i64>>();
+        inputs.extend([-4 << FRAC_BITS, 0, (4 << FRAC_BITS) - 1]);
+        for x in inputs {
+            let x_var = FpVar::new_witness(cs.clone(), || Ok(to_field(x))).unwrap();
+            let approx = x_var
+                .exp_approx(&config, BIT_SIZE)
+                .unwrap()
+                .value()
+                .unwrap();
+            let exact = (x as f64 / one).exp();
+            // The Taylor remainder is below `2^-10` relative to `exp(x)`, and
+            // the coefficient and evaluation rounding add a few units in the
+            // last place.
+            let tolerance = exact / 1024.0 + 4.0 / one;
+            let low = ((exact - tolerance) * one).floor() as i64;
+            let high = ((exact + tolerance) * one).ceil() as i64;
+            assert!((low..=high).any(|y| to_field(y) == approx), "x = {x}");
+        }
+        assert!(cs.is_satisfied().unwrap());
+
+        // Inputs outside of the domain are rejected.
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let x_var = FpVar::new_witness(cs.clone(), || Ok(to_field(4 << FRAC_BITS))).unwrap();
+        let _ = x_var.exp_approx(&config, BIT_SIZE);
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    /// Native counterpart of `quantize`.
+    fn native_quantize(x: i64, scale: i64, zero_point: i64, qmin: i64, qmax: i64) -> i64 {
+        let rounded = (x + scale / 2).div_euclid(scale);
+        (rounded + zero_point).clamp(qmin, qmax)
+    }
+
+    #[test]
+    fn test_quantize() {
+        let mut rng = ark_std::test_rng();
+        let (zero_point, qmin, qmax) = (3, -128, 127);
+        for scale in [16i64, 7, 1] {
+            let mut inputs = vec![0, 1, -1, 2000, -3000, scale / 2, -scale / 2];
+            inputs.extend((0..8).map(|_| rng.gen_range(-2048i64..2048)));
+            // Inputs that land exactly on the clamp boundaries.
+            inputs.push((qmin - zero_point) * scale);
+            inputs.push((qmax - zero_point) * scale);
+
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            for x in inputs {
+                let x_var = FpVar::new_witness(cs.clone(), || Ok(to_field(x))).unwrap();
+                let params = [scale, zero_point, qmin, qmax].map(to_field);
+                let [scale_f, zp, min, max] = params;
+                let q = x_var.quantize(scale_f, zp, min, max, BIT_SIZE).unwrap();
+                let expected = native_quantize(x, scale, zero_point, qmin, qmax);
+                assert_eq!(q.value().unwrap(), to_field(expected), "x = {x}");
+
+                let q = FpVar::constant(to_field(x))
+                    .quantize(scale_f, zp, min, max, BIT_SIZE)
+                    .unwrap();
+                assert_eq!(q.value().unwrap(), to_field(expected));
+            }
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_dequantize() {
+        let mut rng = ark_std::test_rng();
+        let (scale, zero_point, qmin, qmax) = (10i64, -5, -128, 127);
+        let params = [scale, zero_point, qmin, qmax].map(to_field);
+        let [scale_f, zp, min, max] = params;
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        for _ in 0..16 {
+            let x = rng.gen_range(-1200i64..1200);
+            let x_var = FpVar::new_witness(cs.clone(), || Ok(to_field(x))).unwrap();
+            let q = x_var.quantize(scale_f, zp, min, max, BIT_SIZE).unwrap();
+            let num_constraints = cs.num_constraints();
+            let y = q.dequantize(scale_f, zp).unwrap();
+            assert_eq!(cs.num_constraints(), num_constraints);
+
+            let expected = (native_quantize(x, scale, zero_point, qmin, qmax) - zero_point) * scale;
+            assert_eq!(y.value().unwrap(), to_field(expected));
+            // Unclamped values are within one quantization step of the input.
+            assert!((expected - x).abs() <= scale);
+        }
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_signed_fixed_mac() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let a = (0..16)
+            .map(|_| rng.gen_range(-2048i64..2048))
+            .collect::<Vec<_>>();
+        let b = (0..16)
+            .map(|_| rng.gen_range(-2048i64..2048))
+            .collect::<Vec<_>>();
+
+        let mut acc = FpVar::new_witness(cs.clone(), || Ok(Fr::from(0u64))).unwrap();
+        let mut expected = 0i64;
+        let mut exact = 0f64;
+        for (&a, &b) in a.iter().zip(&b) {
+            let a_var = FpVar::new_witness(cs.clone(), || Ok(to_field(a))).unwrap();
+            let b_var = FpVar::new_witness(cs.clone(), || Ok(to_field(b))).unwrap();
+            acc = FpVar::signed_fixed_mac(&acc, &a_var, &b_var, FRAC_BITS, BIT_SIZE).unwrap();
+            expected = native_mac(expected, a, b);
+            exact += (a * b) as f64 / (1 << FRAC_BITS) as f64;
+        }
+        assert_eq!(acc.value().unwrap(), to_field(expected));
+        // Each step rounds by at most half a unit in the last place.
+        assert!((expected as f64 - exact).abs() <= 0.5 * a.len() as f64);
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_signed_fixed_mac_overflow() {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let max = (1i64 << (BIT_SIZE - 1)) - 1;
+        let acc = FpVar::new_witness(cs.clone(), || Ok(to_field(max))).unwrap();
+        let a = FpVar::new_witness(cs.clone(), || Ok(to_field(1 << FRAC_BITS))).unwrap();
+        let _ = FpVar::signed_fixed_mac(&acc, &a, &a, FRAC_BITS, BIT_SIZE).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_fixed_dot_product() {
+        let mut rng = ark_std::test_rng();
+        let a = (0..64)
+            .map(|_| rng.gen_range(-512i64..512))
+            .collect::<Vec<_>>();
+        let b = (0..64)
+            .map(|_| rng.gen_range(-512i64..512))
+            .collect::<Vec<_>>();
+        let full_precision = a.iter().zip(&b).map(|(a, b)| a * b).sum::<i64>();
+        let expected = (full_precision + (1 << (FRAC_BITS - 1))).div_euclid(1 << FRAC_BITS);
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let alloc = |values: &[i64]| {
+            values
+                .iter()
+                .map(|v| FpVar::new_witness(cs.clone(), || Ok(to_field(*v))).unwrap())
+                .collect::<Vec<_>>()
+        };
+        let (a_vars, b_vars) = (alloc(&a), alloc(&b));
+        let num_constraints = cs.num_constraints();
+        let result = FpVar::fixed_dot_product(&a_vars, &b_vars, FRAC_BITS, BIT_SIZE).unwrap();
+        let dot_product_constraints = cs.num_constraints() - num_constraints;
+        assert_eq!(result.value().unwrap(), to_field(expected));
+        assert!(cs.is_satisfied().unwrap());
+
+        let num_constraints = cs.num_constraints();
+        let mut acc = FpVar::new_witness(cs.clone(), || Ok(Fr::from(0u64))).unwrap();
+        for (a, b) in a_vars.iter().zip(&b_vars) {
+            acc = FpVar::signed_fixed_mac(&acc, a, b, FRAC_BITS, BIT_SIZE).unwrap();
+        }
+        let mac_constraints = cs.num_constraints() - num_constraints;
+        assert!(dot_product_constraints < mac_constraints);
+    }
+}
@@ -0,0 +1,258 @@
+use crate::{fields::fp::FpVar, prelude::*, Vec};
+use ark_ff::PrimeField;
+use ark_relations::gr1cs::SynthesisError;
+
+impl<F: PrimeField> FpVar<F> {
+    /// Enforces that `order` is a permutation of `0..n`, and that for every
+    /// edge `(u, v)` in `edges`, `u` appears before `v` in `order`, i.e. that
+    /// `order` is a topological order of the graph with vertices `0..n` and
+    /// edges `edges`.
+    ///
+    /// The permutation check compares every entry of `order` with every
+    /// vertex, which requires `2 * n^2` constraints. The position of each
+    /// endpoint is then read from `order` via equality indicators, which
+    /// also enforces that it is a vertex, and the positions are compared via
+    /// [`Self::is_lt_bounded`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order.len() != n`, or if `n > 2^bit_size`.
+    #[tracing::instrument(target = "gr1cs", skip(order, edges))]
+    pub fn enforce_topological_order(
+        order: &[Self],
+        edges: &[(Self, Self)],
+        n: usize,
+        bit_size: usize,
+    ) -> Result<(), SynthesisError> {
+        assert_eq!(order.len(), n);
+        assert!(bit_size >= usize::BITS as usize || n <= 1 << bit_size);
+        Self::enforce_permutation_of_range(order)?;
+        for (u, v) in edges {
+            let u = Self::position_in(order, u)?;
+            let v = Self::position_in(order, v)?;
+            u.is_lt_bounded(&v, bit_size)?
+                .enforce_equal(&Boolean::TRUE)?;
+        }
+        Ok(())
+    }
+
+    /// Enforces that `colors` is a valid coloring of the graph with vertices
+    /// `0..n` and edges `edges`, i.e. that each color lies in
+    /// `[0, num_colors)`, and that the endpoints of each edge have different
+    /// colors.
+    ///
+    /// Each color is range-checked via [`Self::enforce_bit_size`] and
+    /// [`Self::is_lt_bounded`]. The colors of the endpoints of each edge are
+    /// read from `colors` via equality indicators, which requires `3 * n`
+    /// constraints per endpoint and also enforces that it is a vertex.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `colors.len() != n`, or if `num_colors > 2^bit_size`.
+    #[tracing::instrument(target = "gr1cs", skip(colors, edges))]
+    pub fn enforce_valid_coloring(
+        colors: &[Self],
+        edges: &[(Self, Self)],
+        num_colors: usize,
+        n: usize,
+        bit_size: usize,
+    ) -> Result<(), SynthesisError> {
+        assert_eq!(colors.len(), n);
+        assert!(bit_size >= usize::BITS as usize || num_colors <= 1 << bit_size);
+        let num_colors = Self::constant(F::from(num_colors as u64));
+        for color in colors {
+            color.enforce_bit_size(bit_size)?;
+            color
+                .is_lt_bounded(&num_colors, bit_size)?
+                .enforce_equal(&Boolean::TRUE)?;
+        }
+        for (u, v) in edges {
+            let u = Self::read_at_index(colors, u)?;
+            let v = Self::read_at_index(colors, v)?;
+            u.enforce_not_equal(&v)?;
+        }
+        Ok(())
+    }
+
+    /// Outputs `values[index]`, enforcing that `index` lies in
+    /// `[0, values.len())`.
+    fn read_at_index(values: &[Self], index: &Self) -> Result<Self, SynthesisError> {
+        let positions = (0..values.len() as u64).map(F::from).collect::<Vec<_>>();
+        let indicators = index.equality_indicators(&positions)?;
+        let count: Self = indicators.iter().cloned().map(Self::from).sum();
+        count.enforce_equal(&Self::one())?;
+        let indicators = indicators.into_iter().map(Self::from).collect::<Vec<_>>();
+        Self::inner_product(&indicators, values)
+    }
+
+    /// Enforces that the entries of `values` are pairwise distinct.
+    ///
+    /// This requires one constraint per pair of non-constant entries, i.e.
+    /// `n * (n - 1) / 2` constraints for `n` entries.
+    #[tracing::instrument(target = "gr1cs", skip(values))]
+    pub fn enforce_all_distinct(values: &[Self]) -> Result<(), SynthesisError> {
+        for (i, a) in values.iter().enumerate() {
+            for b in &values[i + 1..] {
+                a.enforce_not_equal(b)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Enforces that every cell referenced by `groups` is smaller than
+    /// `2^bit_size`, and that, within each group, the referenced cells are
+    /// pairwise distinct.
+    ///
+    /// Each group is a list of indices into `cells`, e.g. the rows, columns
+    /// and boxes of a Sudoku grid. Cells that belong to several groups are
+    /// range-checked once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a group refers to an index outside of `cells`.
+    #[tracing::instrument(target = "gr1cs", skip(cells, groups))]
+    pub fn enforce_all_different_groups(
+        cells: &[Self],
+        groups: &[Vec<usize>],
+        bit_size: usize,
+    ) -> Result<(), SynthesisError> {
+        let mut checked = vec![false; cells.len()];
+        for &i in groups.iter().flatten() {
+            if !checked[i] {
+                cells[i].enforce_bit_size(bit_size)?;
+                checked[i] = true;
+            }
+        }
+        for group in groups {
+            let group = group.iter().map(|&i| cells[i].clone()).collect::<Vec<_>>();
+            Self::enforce_all_distinct(&group)?;
+        }
+        Ok(())
+    }
+
+    /// Enforces that `values` is a permutation of `0..values.len()`.
+    ///
+    /// Each entry must equal exactly one element of the range, and each
+    /// element of the range must be equal to exactly one entry.
+    fn enforce_permutation_of_range(values: &[Self]) -> Result<(), SynthesisError> {
+        let range = (0..values.len() as u64).map(F::from).collect::<Vec<_>>();
+        let indicators = values
+            .iter()
+            .map(|value| value.equality_indicators(&range))
+            .collect::<Result<Vec<_>, _>>()?;
+        for row in &indicators {
+            let count: Self = row.iter().cloned().map(Self::from).sum();
+            count.enforce_equal(&Self::one())?;
+        }
+        for k in 0..range.len() {
+            let count: Self = indicators
+                .iter()
+                .map(|row| Self::from(row[k].clone()))
+                .sum();
+            count.enforce_equal(&Self::one())?;
+        }
+        Ok(())
+    }
+
+    /// Outputs the index of `value` in `values`, which must contain `value`
+    /// exactly once.
+    fn position_in(values: &[Self], value: &Self) -> Result<Self, SynthesisError> {
+        let indicators = values
+            .iter()
+            .map(|v| v.is_eq(value).map(Self::from))
+            .collect::<Result<Vec<_>, _>>()?;
+        let count: Self = indicators.iter().sum();
+        count.enforce_equal(&Self::one())?;
+        let positions = (0..values.len() as u64).map(F::from).collect::<Vec<_>>();
+        Self::sparse_weighted_sum(&positions, &indicators)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{alloc::AllocVar, fields::fp::FpVar};
+    use ark_relations::gr1cs::ConstraintSystem;
+    use ark_test_curves::bls12_381::Fr;
+
+    const EDGES: [(u64, u64); 5] = [(0, 1), (0, 2), (1, 3), (2, 3), (3, 4)];
+
+    #[test]
+    fn test_enforce_topological_order() {
+        for (order, edges, expected) in [
+            ([0u64, 1, 2, 3, 4], &EDGES[..], true),
+            ([0, 2, 1, 3, 4], &EDGES[..], true),
+            // `3` appears before `2`, violating the edge `(2, 3)`.
+            ([0, 1, 3, 2, 4], &EDGES[..], false),
+            // Orders must be permutations of the vertices.
+            ([0, 1, 1, 3, 4], &EDGES[..], false),
+            ([0, 1, 2, 3, 5], &EDGES[..], false),
+            // Edges must connect vertices of the graph.
+            ([0, 1, 2, 3, 4], &[(0, 5)][..], false),
+        ] {
+            let cs = ConstraintSystem::new_ref();
+            let alloc = |x: u64| FpVar::new_witness(cs.clone(), || Ok(Fr::from(x))).unwrap();
+            let order = order.map(alloc);
+            let edges = edges
+                .iter()
+                .map(|(u, v)| (alloc(*u), alloc(*v)))
+                .collect::<Vec<_>>();
+            FpVar::enforce_topological_order(&order, &edges, order.len(), 3).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_enforce_valid_coloring() {
+        let edges = [(0u64, 1u64), (1, 2), (2, 0), (2, 3)];
+        for (colors, edges, expected) in [
+            ([0u64, 1, 2, 0], &edges[..], true),
+            ([2, 0, 1, 0], &edges[..], true),
+            // The edge `(2, 3)` is monochromatic.
+            ([0, 1, 2, 2], &edges[..], false),
+            // Only three colors are available.
+            ([0, 1, 3, 0], &edges[..], false),
+            // Edges must connect vertices of the graph.
+            ([0, 1, 2, 0], &[(0, 4)][..], false),
+        ] {
+            let cs = ConstraintSystem::new_ref();
+            let alloc = |x: u64| FpVar::new_witness(cs.clone(), || Ok(Fr::from(x))).unwrap();
+            let colors = colors.map(alloc);
+            let edges = edges
+                .iter()
+                .map(|(u, v)| (alloc(*u), alloc(*v)))
+                .collect::<Vec<_>>();
+            FpVar::enforce_valid_coloring(&colors, &edges, 3, colors.len(), 2).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+
+    /// Outputs the rows, columns and boxes of a 4x4 Sudoku grid.
+    fn sudoku_groups() -> Vec<Vec<usize>> {
+        let rows = (0..4).map(|r| (0..4).map(|c| 4 * r + c).collect::<Vec<_>>());
+        let columns = (0..4).map(|c| (0..4).map(|r| 4 * r + c).collect::<Vec<_>>());
+        let boxes = (0..4).map(|b| {
+            let start = 8 * (b / 2) + 2 * (b % 2);
+            vec![start, start + 1, start + 4, start + 5]
+        });
+        rows.chain(columns).chain(boxes).collect()
+    }
+
+    #[test]
+    fn test_enforce_all_different_groups() {
+        // The rows of the grid are `1234`, `3412`, `2143` and `4321`.
+        let grid = [1u64, 2, 3, 4, 3, 4, 1, 2, 2, 1, 4, 3, 4, 3, 2, 1];
+        // Swapping two cells of the first row repeats values in two columns.
+        let mut repeated = grid;
+        repeated.swap(0, 1);
+        // Cells must fit in three bits.
+        let mut out_of_range = grid;
+        out_of_range[5] = 8;
+
+        for (grid, expected) in [(grid, true), (repeated, false), (out_of_range, false)] {
+            let cs = ConstraintSystem::new_ref();
+            let cells = grid.map(|x| FpVar::new_witness(cs.clone(), || Ok(Fr::from(x))).unwrap());
+            FpVar::enforce_all_different_groups(&cells, &sudoku_groups(), 3).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use crate::{fields::fp::FpVar, prelude::*, Vec};
+use ark_ff::PrimeField;
+use ark_relations::gr1cs::SynthesisError;
+
+impl<F: PrimeField> FpVar<F> {
+    /// Enforces that `output == weights * input + bias`, where `weights` is a
+    /// constant matrix given by its rows, and `bias` is a constant vector.
+    ///
+    /// Each row of the matrix-vector product is a linear combination computed
+    /// via [`Self::sparse_weighted_sum`], so this requires one constraint per
+    /// output element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weights`, `bias` and `output` have different lengths, or if
+    /// a row of `weights` does not have the same length as `input`.
+    #[tracing::instrument(target = "gr1cs", skip(input, weights, bias, output))]
+    pub fn enforce_affine_layer(
+        input: &[Self],
+        weights: &[Vec<F>],
+        bias: &[F],
+        output: &[Self],
+    ) -> Result<(), SynthesisError> {
+        assert_eq!(weights.len(), bias.len());
+        assert_eq!(weights.len(), output.len());
+        for ((row, bias), output) in weights.iter().zip(bias).zip(output) {
+            let product = Self::sparse_weighted_sum(row, input)?;
+            (product + *bias).enforce_equal(output)?;
+        }
+        Ok(())
+    }
+
+    /// Enforces that `output` is the valid one-dimensional convolution of
+    /// `input` with the constant `kernel`, i.e. that
+    /// `output[i] == sum_j kernel[j] * input[i + j]` for every `i`.
+    ///
+    /// As is customary in signal processing and machine learning, the kernel
+    /// is not flipped, and only windows that lie entirely within `input`
+    /// produce an output, so `output.len() == input.len() - kernel.len() + 1`.
+    /// Each window is a linear combination computed via
+    /// [`Self::sparse_weighted_sum`], so this requires one constraint per
+    /// output element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `kernel` is empty or longer than `input`, or if `output`
+    /// does not have the length given above.
+    #[tracing::instrument(target = "gr1cs", skip(input, kernel, output))]
+    pub fn enforce_conv1d(
+        input: &[Self],
+        kernel: &[F],
+        output: &[Self],
+    ) -> Result<(), SynthesisError> {
+        assert!(!kernel.is_empty() && kernel.len() <= input.len());
+        assert_eq!(output.len(), input.len() - kernel.len() + 1);
+        for (window, output) in input.windows(kernel.len()).zip(output) {
+            Self::sparse_weighted_sum(kernel, window)?.enforce_equal(output)?;
+        }
+        Ok(())
+    }
+
+    /// Enforces that `output` is the number-theoretic transform of `input`
+    /// over the subgroup of order `n` generated by `omega`, i.e. that
+    /// `output[k] == sum_j input[j] * omega^(j * k)` for every `k`.
+    ///
+    /// The twiddle factors `omega^(j * k)` are computed natively, so each
+    /// output element is a linear combination computed via
+    /// [`Self::sparse_weighted_sum`], and this requires one constraint per
+    /// output element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `input` or `output` does not have length `n`, or if
+    /// `omega^n != 1`.
+    #[tracing::instrument(target = "gr1cs", skip(input, output))]
+    pub fn enforce_ntt(
+        input: &[Self],
+        output: &[Self],
+        omega: F,
+        n: u64,
+    ) -> Result<(), SynthesisError> {
+        Self::enforce_scaled_transform(input, output, omega, n, F::one())
+    }
+
+    /// Enforces that `time` is the inverse number-theoretic transform of
+    /// `freq` over the subgroup of order `n` generated by `omega`, i.e. that
+    /// `time[j] == n^(-1) * sum_k freq[k] * omega^(-j * k)` for every `j`.
+    ///
+    /// This is the inverse of [`Self::enforce_ntt`]. The normalization by
+    /// `n^(-1)` is folded into the constant twiddle factors, so this also
+    /// requires one constraint per output element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `freq` or `time` does not have length `n`, if
+    /// `omega^n != 1`, or if `n` is a multiple of the characteristic of `F`.
+    #[tracing::instrument(target = "gr1cs", skip(freq, time))]
+    pub fn enforce_intt(
+        freq: &[Self],
+        time: &[Self],
+        omega: F,
+        n: u64,
+    ) -> Result<(), SynthesisError> {
+        // If `omega` is zero, the check `omega^n == 1` below fails.
+        let omega_inv = omega.inverse().unwrap_or_else(F::zero);
+        let n_inv = F::from(n).inverse().expect("`n` must be invertible in `F`");
+        Self::enforce_scaled_transform(freq, time, omega_inv, n, n_inv)
+    }
+
+    /// Enforces that `output[k] == scale * sum_j input[j] * omega^(j * k)`
+    /// for every `k`.
+    fn enforce_scaled_transform(
+        input: &[Self],
+        output: &[Self],
+        omega: F,
+        n: u64,
+        scale: F,
+    ) -> Result<(), SynthesisError> {
+        assert_eq!(input.len() as u64, n);
+        assert_eq!(output.len() as u64, n);
+        assert!(omega.pow([n]).is_one());
+        // `omega_k` is `omega^k`, and the `k`-th row of the transform matrix
+        // consists of its powers.
+        let mut omega_k = F::one();
+        for output in output {
+            let row = ark_std::iter::successors(Some(scale), |w| Some(*w * omega_k))
+                .take(input.len())
+                .collect::<Vec<_>>();
+            Self::sparse_weighted_sum(&row, input)?.enforce_equal(output)?;
+            omega_k *= omega;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{alloc::AllocVar, fields::fp::FpVar};
+    use ark_ff::{FftField, Field};
+    use ark_relations::gr1cs::ConstraintSystem;
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::Fr;
+
+    #[test]
+    fn test_enforce_affine_layer() {
+        let mut rng = ark_std::test_rng();
+        let input = (0..4).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let weights = (0..3)
+            .map(|_| (0..4).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let bias = (0..3).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let output = weights
+            .iter()
+            .zip(&bias)
+            .map(|(row, bias)| row.iter().zip(&input).map(|(w, x)| *w * x).sum::<Fr>() + bias)
+            .collect::<Vec<_>>();
+
+        for wrong in [None, Some(2)] {
+            let cs = ConstraintSystem::new_ref();
+            let input = input
+                .iter()
+                .map(|x| FpVar::new_witness(cs.clone(), || Ok(*x)).unwrap())
+                .collect::<Vec<_>>();
+            let mut output = output.clone();
+            if let Some(i) = wrong {
+                output[i] += Fr::from(1u64);
+            }
+            let output = output
+                .iter()
+                .map(|y| FpVar::new_input(cs.clone(), || Ok(*y)).unwrap())
+                .collect::<Vec<_>>();
+            FpVar::enforce_affine_layer(&input, &weights, &bias, &output).unwrap();
+            assert_eq!(cs.num_constraints(), output.len());
+            assert_eq!(cs.is_satisfied().unwrap(), wrong.is_none());
+        }
+    }
+
+    #[test]
+    fn test_enforce_conv1d() {
+        let input = [3u64, 1, 4, 1, 5, 9, 2, 6];
+        let kernel = [Fr::from(1u64), Fr::from(0u64), -Fr::from(2u64)];
+        let output = input
+            .windows(kernel.len())
+            .map(|window| {
+                let window = window.iter().map(|x| Fr::from(*x));
+                window.zip(&kernel).map(|(x, k)| x * k).sum::<Fr>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(output.len(), 6);
+        let mut wrong = output.clone();
+        wrong[4] += Fr::from(1u64);
+
+        for (kernel, output, expected) in [
+            (&kernel[..], &output[..], true),
+            (&kernel[..], &wrong[..], false),
+            // A kernel as long as the input yields a single output.
+            (&[Fr::from(1u64); 8][..], &[Fr::from(31u64)][..], true),
+        ] {
+            let cs = ConstraintSystem::new_ref();
+            let input = input.map(|x| FpVar::new_witness(cs.clone(), || Ok(Fr::from(x))).unwrap());
+            let output = output
+                .iter()
+                .map(|y| FpVar::new_input(cs.clone(), || Ok(*y)).unwrap())
+                .collect::<Vec<_>>();
+            FpVar::enforce_conv1d(&input, kernel, &output).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_enforce_ntt() {
+        let mut rng = ark_std::test_rng();
+        let omega = Fr::get_root_of_unity(8).unwrap();
+        let input = (0..8).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let output = (0..8u64)
+            .map(|k| {
+                let terms = input.iter().enumerate();
+                terms
+                    .map(|(j, x)| omega.pow([j as u64 * k]) * x)
+                    .sum::<Fr>()
+            })
+            .collect::<Vec<_>>();
+        let mut wrong = output.clone();
+        wrong[5] += Fr::from(1u64);
+
+        for (output, expected) in [(output, true), (wrong, false)] {
+            let cs = ConstraintSystem::new_ref();
+            let input = input
+                .iter()
+                .map(|x| FpVar::new_witness(cs.clone(), || Ok(*x)).unwrap())
+                .collect::<Vec<_>>();
+            let output = output
+                .iter()
+                .map(|y| FpVar::new_input(cs.clone(), || Ok(*y)).unwrap())
+                .collect::<Vec<_>>();
+            FpVar::enforce_ntt(&input, &output, omega, input.len() as u64).unwrap();
+            assert_eq!(cs.num_constraints(), output.len());
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_enforce_intt_round_trip() {
+        let mut rng = ark_std::test_rng();
+        let omega = Fr::get_root_of_unity(8).unwrap();
+        let time = (0..8).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+
+        let cs = ConstraintSystem::new_ref();
+        let time_var = time
+            .iter()
+            .map(|x| FpVar::new_witness(cs.clone(), || Ok(*x)).unwrap())
+            .collect::<Vec<_>>();
+        let freq = (0..8u64)
+            .map(|k| {
+                let terms = time.iter().enumerate();
+                terms
+                    .map(|(j, x)| omega.pow([j as u64 * k]) * x)
+                    .sum::<Fr>()
+            })
+            .collect::<Vec<_>>();
+        let freq = freq
+            .iter()
+            .map(|y| FpVar::new_witness(cs.clone(), || Ok(*y)).unwrap())
+            .collect::<Vec<_>>();
+        FpVar::enforce_ntt(&time_var, &freq, omega, 8).unwrap();
+
+        // Transforming back recovers the original vector.
+        let recovered = time
+            .iter()
+            .map(|x| FpVar::new_input(cs.clone(), || Ok(*x)).unwrap())
+            .collect::<Vec<_>>();
+        FpVar::enforce_intt(&freq, &recovered, omega, 8).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+
+        let mut wrong = time.clone();
+        wrong[3] += Fr::from(1u64);
+        let wrong = wrong
+            .iter()
+            .map(|x| FpVar::new_input(cs.clone(), || Ok(*x)).unwrap())
+            .collect::<Vec<_>>();
+        FpVar::enforce_intt(&freq, &wrong, omega, 8).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+}
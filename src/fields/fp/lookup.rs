@@ -0,0 +1,499 @@
+use crate::{fields::fp::FpVar, prelude::*, Assignment, Vec};
+use ark_ff::{batch_inversion, PrimeField};
+use ark_relations::gr1cs::SynthesisError;
+
+impl<F: PrimeField> FpVar<F> {
+    /// Outputs, for each key in `keys`, the bit `self == key`.
+    ///
+    /// This requires two constraints per non-constant comparison.
+    pub(super) fn equality_indicators(
+        &self,
+        keys: &[F],
+    ) -> Result<Vec<Boolean<F>>, SynthesisError> {
+        keys.iter()
+            .map(|key| self.is_eq(&Self::constant(*key)))
+            .collect()
+    }
+
+    /// Enforces that `value` equals the entry associated with `tag` in the
+    /// constant key-value map `table`.
+    ///
+    /// The constraint system will not be satisfied if `tag` does not match
+    /// exactly one key of `table`; in particular, `table` should not contain
+    /// duplicate keys.
+    #[tracing::instrument(target = "gr1cs", skip(table))]
+    pub fn enforce_tagged_value(
+        tag: &Self,
+        value: &Self,
+        table: &[(F, F)],
+    ) -> Result<(), SynthesisError> {
+        let keys = table.iter().map(|(key, _)| *key).collect::<Vec<_>>();
+        let indicators = tag.equality_indicators(&keys)?;
+
+        // Exactly one indicator must be set.
+        let num_matches: Self = indicators.iter().cloned().map(Self::from).sum();
+        num_matches.enforce_equal(&Self::one())?;
+
+        // Since the entries are constants, this sum is linear in the
+        // indicators, and requires no constraints.
+        let expected: Self = indicators
+            .into_iter()
+            .zip(table)
+            .map(|(indicator, (_, entry))| Self::from(indicator) * *entry)
+            .sum();
+        value.enforce_equal(&expected)
+    }
+
+    /// Outputs the entry of `values` whose corresponding key in `keys` equals
+    /// `self`, or `default` if no key matches, together with a bit indicating
+    /// whether any key matched.
+    ///
+    /// The keys should be distinct; otherwise, the output value is the sum of
+    /// all matching entries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `keys` and `values` have different lengths.
+    #[tracing::instrument(target = "gr1cs", skip(keys, values))]
+    pub fn match_constant(
+        &self,
+        keys: &[F],
+        values: &[Self],
+        default: &Self,
+    ) -> Result<(Self, Boolean<F>), SynthesisError> {
+        assert_eq!(keys.len(), values.len());
+        if keys.is_empty() {
+            return Ok((default.clone(), Boolean::FALSE));
+        }
+        let indicators = self.equality_indicators(keys)?;
+        let matched = Boolean::kary_or(&indicators)?;
+        let selected: Self = indicators
+            .into_iter()
+            .zip(values)
+            .map(|(indicator, value)| Self::from(indicator) * value)
+            .sum();
+        let result = matched.select(&selected, default)?;
+        Ok((result, matched))
+    }
+
+    /// Outputs `self` if `mask` is `true`, and zero otherwise.
+    ///
+    /// This requires at most one constraint, and none if `self` or `mask` is
+    /// a constant.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn masked(&self, mask: &Boolean<F>) -> Result<Self, SynthesisError> {
+        Ok(Self::from(mask.clone()) * self)
+    }
+
+    /// Outputs `self + delta` if `cond` is `true`, and `self` otherwise.
+    ///
+    /// This computes `self + cond * delta`, and so requires at most one
+    /// constraint, and none if `cond` or `delta` is a constant.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn conditionally_add(
+        &self,
+        cond: &Boolean<F>,
+        delta: &Self,
+    ) -> Result<Self, SynthesisError> {
+        Ok(self + delta.masked(cond)?)
+    }
+
+    /// Outputs `sum_i mask[i] * values[i]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values` and `mask` have different lengths.
+    #[tracing::instrument(target = "gr1cs", skip(values, mask))]
+    pub fn masked_sum(values: &[Self], mask: &[Boolean<F>]) -> Result<Self, SynthesisError> {
+        assert_eq!(values.len(), mask.len());
+        let masked = values
+            .iter()
+            .zip(mask)
+            .map(|(value, mask)| value.masked(mask))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(masked.into_iter().sum())
+    }
+
+    /// Enforces that, for each `i`, `counts[i]` equals the number of entries of
+    /// `data` that are equal to `buckets[i]`.
+    ///
+    /// Entries of `data` that do not fall into any bucket are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buckets` and `counts` have different lengths.
+    #[tracing::instrument(target = "gr1cs", skip(data, buckets, counts))]
+    pub fn enforce_histogram(
+        data: &[Self],
+        buckets: &[F],
+        counts: &[Self],
+    ) -> Result<(), SynthesisError> {
+        assert_eq!(buckets.len(), counts.len());
+        let indicators = data
+            .iter()
+            .map(|d| d.equality_indicators(buckets))
+            .collect::<Result<Vec<_>, _>>()?;
+        for (i, count) in counts.iter().enumerate() {
+            let occurrences: Self = indicators
+                .iter()
+                .map(|indicators| Self::from(indicators[i].clone()))
+                .sum();
+            occurrences.enforce_equal(count)?;
+        }
+        Ok(())
+    }
+
+    /// Outputs `prod_i (challenge + values[i])`.
+    ///
+    /// This requires one constraint per value.
+    pub(super) fn grand_product(values: &[Self], challenge: &Self) -> Self {
+        values
+            .iter()
+            .fold(Self::one(), |acc, value| acc * (challenge + value))
+    }
+
+    /// Enforces that `a` and `b` represent the same multiset, i.e. that `b` is
+    /// a permutation of `a`, via the identity
+    /// `prod_i (challenge + a[i]) == prod_i (challenge + b[i])`.
+    ///
+    /// For this to be sound, `challenge` must be a random challenge that is
+    /// chosen after `a` and `b` have been fixed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` and `b` have different lengths.
+    #[tracing::instrument(target = "gr1cs", skip(a, b))]
+    pub fn enforce_multiset_equal(
+        a: &[Self],
+        b: &[Self],
+        challenge: &Self,
+    ) -> Result<(), SynthesisError> {
+        assert_eq!(a.len(), b.len());
+        Self::grand_product(a, challenge).enforce_equal(&Self::grand_product(b, challenge))
+    }
+
+    /// Enforces that every entry of `a` appears in `b`, where the `j`-th
+    /// entry of `b` appears `m[j] = multiplicities[j]` times in `a`, via the
+    /// identity `prod_i (challenge + a[i]) == prod_j (challenge + b[j])^m[j]`.
+    ///
+    /// The entries of `b` should be distinct. This method enforces that each
+    /// multiplicity fits in as many bits as `a.len()`, and raises each factor
+    /// to its multiplicity via square-and-multiply.
+    ///
+    /// For this to be sound, `challenge` must be a random challenge that is
+    /// chosen after `a`, `b` and `multiplicities` have been fixed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `b` and `multiplicities` have different lengths.
+    #[tracing::instrument(target = "gr1cs", skip(a, b, multiplicities))]
+    pub fn enforce_multiset_subset(
+        a: &[Self],
+        b: &[Self],
+        multiplicities: &[Self],
+        challenge: &Self,
+    ) -> Result<(), SynthesisError> {
+        assert_eq!(b.len(), multiplicities.len());
+        let num_bits = (usize::BITS - a.len().leading_zeros()) as usize;
+        let mut b_product = Self::one();
+        for (b, multiplicity) in b.iter().zip(multiplicities) {
+            let (bits, _) = multiplicity.to_bits_le_with_top_bits_zero(num_bits)?;
+            b_product *= (challenge + b).pow_le(&bits)?;
+        }
+        Self::grand_product(a, challenge).enforce_equal(&b_product)
+    }
+
+    /// Outputs `numerators[i] / denominators[i]` for each `i`, enforcing that
+    /// `fractions[i] * denominators[i] == numerators[i]`.
+    ///
+    /// The fractions are computed natively via a single batch inversion, and
+    /// each fraction requires one constraint.
+    fn batch_fractions(
+        numerators: &[Self],
+        denominators: &[Self],
+    ) -> Result<Vec<Self>, SynthesisError> {
+        let cs = numerators.cs().or(denominators.cs());
+        let mode = if cs.is_none() {
+            AllocationMode::Constant
+        } else {
+            AllocationMode::Witness
+        };
+        let inverses = denominators
+            .iter()
+            .map(|d| d.value())
+            .collect::<Result<Vec<_>, _>>()
+            .ok()
+            .map(|mut inverses| {
+                batch_inversion(&mut inverses);
+                inverses
+            });
+        numerators
+            .iter()
+            .zip(denominators)
+            .enumerate()
+            .map(|(i, (numerator, denominator))| {
+                let fraction = Self::new_variable(
+                    cs.clone(),
+                    || Ok(numerator.value()? * inverses.get()?[i]),
+                    mode,
+                )?;
+                fraction.mul_equals(denominator, numerator)?;
+                Ok(fraction)
+            })
+            .collect()
+    }
+
+    /// Enforces that every entry of `a` appears in `b`, where the `j`-th
+    /// entry of `b` appears `m[j] = multiplicities[j]` times in `a`, via the
+    /// log-derivative identity
+    /// `sum_i 1 / (challenge + a[i]) == sum_j m[j] / (challenge + b[j])`.
+    ///
+    /// The fractions on both sides are computed with one batch inversion
+    /// each, and require one constraint per entry of `a` and `b`. Unlike
+    /// [`Self::enforce_multiset_subset`], the multiplicities do not need to
+    /// be decomposed into bits.
+    ///
+    /// The entries of `b` should be distinct, and the multiplicities should
+    /// be smaller than the characteristic of `F`. For this to be sound,
+    /// `challenge` must be a random challenge that is chosen after `a`, `b`
+    /// and `multiplicities` have been fixed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `b` and `multiplicities` have different lengths.
+    #[tracing::instrument(target = "gr1cs", skip(a, b, multiplicities))]
+    pub fn logderiv_lookup(
+        a: &[Self],
+        b: &[Self],
+        multiplicities: &[Self],
+        challenge: &Self,
+    ) -> Result<(), SynthesisError> {
+        assert_eq!(b.len(), multiplicities.len());
+        let shift = |values: &[Self]| values.iter().map(|v| challenge + v).collect::<Vec<_>>();
+        let ones = vec![Self::one(); a.len()];
+        let a_sum: Self = Self::batch_fractions(&ones, &shift(a))?.into_iter().sum();
+        let b_sum: Self = Self::batch_fractions(multiplicities, &shift(b))?
+            .into_iter()
+            .sum();
+        a_sum.enforce_equal(&b_sum)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        alloc::{AllocVar, AllocationMode},
+        boolean::Boolean,
+        fields::fp::FpVar,
+        test_utils::modes,
+        GR1CSVar,
+    };
+    use ark_relations::gr1cs::ConstraintSystem;
+    use ark_std::{vec::Vec, UniformRand, Zero};
+    use ark_test_curves::bls12_381::Fr;
+
+    #[test]
+    fn test_enforce_tagged_value() {
+        let table = [(1u64, 10u64), (2, 20), (7, 70)].map(|(k, v)| (Fr::from(k), Fr::from(v)));
+        for (tag, value, expected) in [
+            (1u64, 10u64, true),
+            (2, 20, true),
+            (7, 70, true),
+            (1, 20, false),
+            (7, 0, false),
+            // Unknown tags are rejected, even with a zero value.
+            (3, 0, false),
+        ] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let tag = FpVar::new_witness(cs.clone(), || Ok(Fr::from(tag))).unwrap();
+            let value = FpVar::new_witness(cs.clone(), || Ok(Fr::from(value))).unwrap();
+            FpVar::enforce_tagged_value(&tag, &value, &table).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_match_constant() {
+        let keys = [3u64, 5, 8].map(Fr::from);
+        for (input, expected, matched) in [
+            (3u64, 30u64, true),
+            (5, 50, true),
+            (8, 80, true),
+            (4, 99, false),
+        ] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let input = FpVar::new_witness(cs.clone(), || Ok(Fr::from(input))).unwrap();
+            let values = [30u64, 50, 80]
+                .map(|v| FpVar::new_witness(cs.clone(), || Ok(Fr::from(v))).unwrap());
+            let default = FpVar::new_witness(cs.clone(), || Ok(Fr::from(99u64))).unwrap();
+            let (result, is_match) = input.match_constant(&keys, &values, &default).unwrap();
+            assert_eq!(result.value().unwrap(), Fr::from(expected));
+            assert_eq!(is_match.value().unwrap(), matched);
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    fn check_conditionally_add(modes: [AllocationMode; 3], cond: bool) {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let (acc, delta) = (Fr::rand(&mut rng), Fr::rand(&mut rng));
+        let acc_var = FpVar::new_variable(cs.clone(), || Ok(acc), modes[0]).unwrap();
+        let cond_var = Boolean::new_variable(cs.clone(), || Ok(cond), modes[1]).unwrap();
+        let delta_var = FpVar::new_variable(cs.clone(), || Ok(delta), modes[2]).unwrap();
+
+        let num_constraints = cs.num_constraints();
+        let result = acc_var.conditionally_add(&cond_var, &delta_var).unwrap();
+        let expected = if cond { acc + delta } else { acc };
+        assert_eq!(result.value().unwrap(), expected);
+        let both_variables = !cond_var.is_constant() && !delta_var.is_constant();
+        assert_eq!(
+            cs.num_constraints() - num_constraints,
+            both_variables as usize
+        );
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_conditionally_add() {
+        for acc_mode in modes() {
+            for cond_mode in modes() {
+                for delta_mode in modes() {
+                    for cond in [false, true] {
+                        check_conditionally_add([acc_mode, cond_mode, delta_mode], cond);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_masked_sum() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let mut expected = Fr::zero();
+        let mut values = Vec::new();
+        let mut mask = Vec::new();
+        for i in 0..10 {
+            let value = Fr::rand(&mut rng);
+            let bit = i % 3 != 0;
+            if bit {
+                expected += value;
+            }
+            values.push(FpVar::new_witness(cs.clone(), || Ok(value)).unwrap());
+            mask.push(Boolean::new_witness(cs.clone(), || Ok(bit)).unwrap());
+        }
+        let sum = FpVar::masked_sum(&values, &mask).unwrap();
+        assert_eq!(sum.value().unwrap(), expected);
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_enforce_histogram() {
+        let buckets = [1u64, 2, 5].map(Fr::from);
+        for (counts, expected) in [([2u64, 3, 1], true), ([2, 4, 1], false), ([2, 3, 0], false)] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let data = [1u64, 2, 2, 5, 1, 2, 9]
+                .map(|d| FpVar::new_witness(cs.clone(), || Ok(Fr::from(d))).unwrap());
+            let counts =
+                counts.map(|c| FpVar::new_witness(cs.clone(), || Ok(Fr::from(c))).unwrap());
+            FpVar::enforce_histogram(&data, &buckets, &counts).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_enforce_multiset_equal() {
+        let mut rng = ark_std::test_rng();
+        let a = [4u64, 8, 15, 16, 23, 42, 8];
+        for (b, expected) in [
+            (a, true),
+            ([42, 8, 23, 4, 16, 8, 15], true),
+            ([42, 8, 23, 4, 16, 8, 14], false),
+            // Multiplicities matter.
+            ([4, 8, 15, 16, 23, 42, 42], false),
+        ] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let alloc = |values: &[u64]| {
+                values
+                    .iter()
+                    .map(|v| FpVar::new_witness(cs.clone(), || Ok(Fr::from(*v))).unwrap())
+                    .collect::<Vec<_>>()
+            };
+            let (a, b) = (alloc(&a), alloc(&b));
+            let challenge = FpVar::new_input(cs.clone(), || Ok(Fr::rand(&mut rng))).unwrap();
+            FpVar::enforce_multiset_equal(&a, &b, &challenge).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_enforce_multiset_subset() {
+        let mut rng = ark_std::test_rng();
+        for (a, multiplicities, expected) in [
+            (&[3u64, 5, 3, 9, 3][..], [3u64, 1, 1, 0], true),
+            (&[][..], [0, 0, 0, 0], true),
+            // Wrong multiplicities.
+            (&[3, 5, 3, 9, 3][..], [2, 1, 1, 0], false),
+            (&[3, 5, 3, 9, 3][..], [3, 1, 1, 1], false),
+            // `4` does not appear in `b`.
+            (&[3, 5, 4, 9, 3][..], [2, 1, 1, 0], false),
+        ] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let a = a
+                .iter()
+                .map(|v| FpVar::new_witness(cs.clone(), || Ok(Fr::from(*v))).unwrap())
+                .collect::<Vec<_>>();
+            let b = [3u64, 5, 9, 11]
+                .map(|v| FpVar::new_witness(cs.clone(), || Ok(Fr::from(v))).unwrap());
+            let multiplicities =
+                multiplicities.map(|m| FpVar::new_witness(cs.clone(), || Ok(Fr::from(m))).unwrap());
+            let challenge = FpVar::new_input(cs.clone(), || Ok(Fr::rand(&mut rng))).unwrap();
+            FpVar::enforce_multiset_subset(&a, &b, &multiplicities, &challenge).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_logderiv_lookup() {
+        let mut rng = ark_std::test_rng();
+        for (a, multiplicities, expected) in [
+            (&[3u64, 5, 3, 9, 3][..], [3u64, 1, 1, 0], true),
+            (&[11; 6][..], [0, 0, 0, 6], true),
+            // Wrong multiplicities.
+            (&[3, 5, 3, 9, 3][..], [2, 1, 1, 0], false),
+            // `4` does not appear in `b`.
+            (&[3, 5, 4, 9, 3][..], [2, 1, 1, 0], false),
+        ] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let a = a
+                .iter()
+                .map(|v| FpVar::new_witness(cs.clone(), || Ok(Fr::from(*v))).unwrap())
+                .collect::<Vec<_>>();
+            let b = [3u64, 5, 9, 11]
+                .map(|v| FpVar::new_witness(cs.clone(), || Ok(Fr::from(v))).unwrap());
+            let multiplicities =
+                multiplicities.map(|m| FpVar::new_witness(cs.clone(), || Ok(Fr::from(m))).unwrap());
+            let challenge = FpVar::new_input(cs.clone(), || Ok(Fr::rand(&mut rng))).unwrap();
+
+            let num_witnesses = cs.num_witness_variables();
+            FpVar::logderiv_lookup(&a, &b, &multiplicities, &challenge).unwrap();
+            // One fraction is allocated per entry of `a` and `b`.
+            assert_eq!(
+                cs.num_witness_variables(),
+                num_witnesses + a.len() + b.len()
+            );
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_logderiv_lookup_constants() {
+        let a = [3u64, 3, 9].map(|v| FpVar::Constant(Fr::from(v)));
+        let b = [3u64, 9].map(|v| FpVar::Constant(Fr::from(v)));
+        let challenge = FpVar::Constant(Fr::from(1000u64));
+        let multiplicities = [2u64, 1].map(|m| FpVar::Constant(Fr::from(m)));
+        FpVar::logderiv_lookup(&a, &b, &multiplicities, &challenge).unwrap();
+        let multiplicities = [1u64, 2].map(|m| FpVar::Constant(Fr::from(m)));
+        assert!(FpVar::logderiv_lookup(&a, &b, &multiplicities, &challenge).is_err());
+    }
+}
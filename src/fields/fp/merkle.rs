@@ -0,0 +1,203 @@
+use crate::{fields::fp::FpVar, prelude::*, Vec};
+use ark_ff::PrimeField;
+use ark_relations::gr1cs::SynthesisError;
+
+impl<F: PrimeField> FpVar<F> {
+    /// Outputs the root of the Merkle tree obtained by hashing `leaf` along
+    /// `path`.
+    ///
+    /// `path` is ordered from the leaf to the root. Each entry consists of the
+    /// sibling of the current node and a direction bit, which is set if the
+    /// current node is the right child of its parent. The parent of `left`
+    /// and `right` is `hash(left, right)`.
+    ///
+    /// Each level requires two constraints plus the cost of `hash`.
+    #[tracing::instrument(target = "gr1cs", skip(path, hash))]
+    pub fn merkle_path_root(
+        leaf: &Self,
+        path: &[(Self, Boolean<F>)],
+        hash: impl Fn(&Self, &Self) -> Result<Self, SynthesisError>,
+    ) -> Result<Self, SynthesisError> {
+        let mut current = leaf.clone();
+        for (sibling, is_right) in path {
+            let left = is_right.select(sibling, &current)?;
+            let right = is_right.select(&current, sibling)?;
+            current = hash(&left, &right)?;
+        }
+        Ok(current)
+    }
+
+    /// Enforces that `leaf` is included in the Merkle tree with root `root`,
+    /// via the authentication path `path`.
+    ///
+    /// See [`Self::merkle_path_root`] for the format of `path`.
+    #[tracing::instrument(target = "gr1cs", skip(path, hash))]
+    pub fn verify_merkle_path(
+        leaf: &Self,
+        path: &[(Self, Boolean<F>)],
+        root: &Self,
+        hash: impl Fn(&Self, &Self) -> Result<Self, SynthesisError>,
+    ) -> Result<(), SynthesisError> {
+        Self::merkle_path_root(leaf, path, hash)?.enforce_equal(root)
+    }
+
+    /// Enforces that every `(leaf, path)` in `leaves` is included in the
+    /// Merkle tree with root `root`.
+    ///
+    /// Each path is verified via [`Self::verify_merkle_path`] with the same
+    /// `hash`.
+    #[tracing::instrument(target = "gr1cs", skip(leaves, hash))]
+    pub fn verify_merkle_batch(
+        leaves: &[(Self, Vec<(Self, Boolean<F>)>)],
+        root: &Self,
+        hash: impl Fn(&Self, &Self) -> Result<Self, SynthesisError>,
+    ) -> Result<(), SynthesisError> {
+        for (leaf, path) in leaves {
+            Self::verify_merkle_path(leaf, path, root, &hash)?;
+        }
+        Ok(())
+    }
+
+    /// Enforces that the top `shared_depth` levels of `path_a` and `path_b`
+    /// agree, i.e. that both the direction bits and the siblings of the
+    /// paths are equal from the root down to depth `shared_depth`.
+    ///
+    /// Paths are ordered from the leaf to the root, as in
+    /// [`Self::merkle_path_root`], so these are the last `shared_depth`
+    /// entries of each path. If both paths are valid for the same root, this
+    /// implies that their leaves lie in the same subtree of depth
+    /// `path_a.len() - shared_depth`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the paths have different lengths, or if `shared_depth`
+    /// exceeds their length.
+    #[tracing::instrument(target = "gr1cs", skip(path_a, path_b))]
+    pub fn enforce_common_prefix(
+        path_a: &[(Self, Boolean<F>)],
+        path_b: &[(Self, Boolean<F>)],
+        shared_depth: usize,
+    ) -> Result<(), SynthesisError> {
+        assert_eq!(path_a.len(), path_b.len());
+        assert!(shared_depth <= path_a.len());
+        let start = path_a.len() - shared_depth;
+        for ((sibling_a, is_right_a), (sibling_b, is_right_b)) in
+            path_a[start..].iter().zip(&path_b[start..])
+        {
+            sibling_a.enforce_equal(sibling_b)?;
+            is_right_a.enforce_equal(is_right_b)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{alloc::AllocVar, boolean::Boolean, fields::fp::FpVar};
+    use ark_relations::gr1cs::{ConstraintSystem, ConstraintSystemRef, SynthesisError};
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::Fr;
+
+    const DEPTH: usize = 3;
+
+    /// A (cryptographically useless) stand-in for a two-to-one hash function.
+    fn mock_hash(left: &FpVar<Fr>, right: &FpVar<Fr>) -> Result<FpVar<Fr>, SynthesisError> {
+        Ok(left * left + right * Fr::from(3u64) + Fr::from(7u64))
+    }
+
+    fn native_mock_hash(left: Fr, right: Fr) -> Fr {
+        left * left + right * Fr::from(3u64) + Fr::from(7u64)
+    }
+
+    /// Outputs the layers of the Merkle tree over `leaves`, from the leaves
+    /// to the root.
+    fn tree(leaves: &[Fr]) -> Vec<Vec<Fr>> {
+        let mut layers = vec![leaves.to_vec()];
+        while layers.last().unwrap().len() > 1 {
+            let layer = layers.last().unwrap();
+            let parents = layer
+                .chunks(2)
+                .map(|pair| native_mock_hash(pair[0], pair[1]))
+                .collect();
+            layers.push(parents);
+        }
+        layers
+    }
+
+    /// Outputs the authentication path of the `index`-th leaf.
+    fn native_path(layers: &[Vec<Fr>], mut index: usize) -> Vec<(Fr, bool)> {
+        let mut path = Vec::new();
+        for layer in &layers[..layers.len() - 1] {
+            path.push((layer[index ^ 1], index & 1 == 1));
+            index /= 2;
+        }
+        path
+    }
+
+    fn alloc_path(
+        cs: &ConstraintSystemRef<Fr>,
+        path: &[(Fr, bool)],
+    ) -> Vec<(FpVar<Fr>, Boolean<Fr>)> {
+        path.iter()
+            .map(|(sibling, is_right)| {
+                (
+                    FpVar::new_witness(cs.clone(), || Ok(*sibling)).unwrap(),
+                    Boolean::new_witness(cs.clone(), || Ok(*is_right)).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_verify_merkle_batch() {
+        let mut rng = ark_std::test_rng();
+        let leaves = (0..1 << DEPTH)
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let layers = tree(&leaves);
+        let root = layers[DEPTH][0];
+
+        for bad in [None, Some(1)] {
+            let cs = ConstraintSystem::new_ref();
+            let root_var = FpVar::new_input(cs.clone(), || Ok(root)).unwrap();
+            let batch = [0, 3, 6]
+                .iter()
+                .enumerate()
+                .map(|(i, &index)| {
+                    let mut path = native_path(&layers, index);
+                    if bad == Some(i) {
+                        path[1].0 += Fr::from(1u64);
+                    }
+                    let leaf = FpVar::new_witness(cs.clone(), || Ok(leaves[index])).unwrap();
+                    (leaf, alloc_path(&cs, &path))
+                })
+                .collect::<Vec<_>>();
+            FpVar::verify_merkle_batch(&batch, &root_var, mock_hash).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), bad.is_none());
+        }
+    }
+
+    #[test]
+    fn test_enforce_common_prefix() {
+        let mut rng = ark_std::test_rng();
+        let leaves = (0..1 << DEPTH)
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let layers = tree(&leaves);
+        // Leaves 0 and 1 are siblings, while leaves 0 and 3 only share
+        // the top level of their paths.
+        for (a, b, shared_depth, expected) in [
+            (0, 1, 2, true),
+            (0, 3, 1, true),
+            (0, 3, 2, false),
+            (0, 5, 0, true),
+            (0, 5, 1, false),
+        ] {
+            let cs = ConstraintSystem::new_ref();
+            let path_a = alloc_path(&cs, &native_path(&layers, a));
+            let path_b = alloc_path(&cs, &native_path(&layers, b));
+            FpVar::enforce_common_prefix(&path_a, &path_b, shared_depth).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+}
@@ -2,12 +2,25 @@ use ark_ff::{BigInteger, PrimeField};
 use ark_relations::gr1cs::{
     ConstraintSystemRef, LinearCombination, Namespace, SynthesisError, Variable,
 };
-use ark_std::{borrow::Borrow, iter::Sum, vec::Vec};
+use ark_std::{any::TypeId, borrow::Borrow, collections::BTreeMap, iter::Sum, vec::Vec};
 use itertools::zip_eq;
 
 use crate::{boolean::AllocatedBool, convert::ToConstraintFieldGadget, prelude::*, Assignment};
 
+mod bounded;
 mod cmp;
+mod commitment;
+mod evaluation;
+mod fixed_point;
+mod graph;
+mod linear;
+mod lookup;
+mod merkle;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod trace;
+
+pub use fixed_point::PiecewisePolyConfig;
 
 /// Represents a variable in the constraint system whose
 /// value can be an arbitrary field element.
@@ -31,6 +44,67 @@ impl<F: PrimeField> AllocatedFp<F> {
             cs,
         }
     }
+
+    /// Allocates the constant `v` in `cs`, reusing the variable created by a
+    /// previous call with the same `v` and `cs`.
+    ///
+    /// Allocating a constant via [`AllocVar::new_constant`] creates a fresh
+    /// linear combination each time, which bloats the constraint system when
+    /// the same constant is allocated many times. This instead interns the
+    /// constants of `cs` in its cache map, keyed by value, so only the first
+    /// allocation of each value creates a linear combination. Zero and one
+    /// never need one, as they are represented by `Variable::Zero` and
+    /// `Variable::One`.
+    pub fn new_constant_cached(cs: ConstraintSystemRef<F>, v: F) -> Result<Self, SynthesisError> {
+        if cs.is_none() || v.is_zero() || v.is_one() {
+            return Self::new_constant(cs, v);
+        }
+        let variable = Self::cached_lc(&cs, v, || (v, Variable::One).into())?;
+        Ok(Self::new(Some(v), variable, cs))
+    }
+
+    /// Outputs a variable for the linear combination `lc`, which is
+    /// identified by `key`, reusing the variable created by a previous call
+    /// with the same key and the same type of key in `cs`.
+    fn cached_lc<K: Ord + 'static>(
+        cs: &ConstraintSystemRef<F>,
+        key: K,
+        lc: impl FnOnce() -> LinearCombination<F>,
+    ) -> Result<Variable, SynthesisError> {
+        let cached = Self::with_cache(cs, |c: &mut LcCache<K>| c.0.get(&key).copied()).flatten();
+        if let Some(variable) = cached {
+            return Ok(variable);
+        }
+        let variable = cs.new_lc(lc)?;
+        Self::with_cache(cs, |c: &mut LcCache<K>| c.0.insert(key, variable));
+        Ok(variable)
+    }
+
+    /// Runs `f` on the cache of type `T` in the cache map of `cs`, creating
+    /// an empty cache first if needed.
+    ///
+    /// Outputs `None` if `cs` is `ConstraintSystemRef::None`.
+    fn with_cache<T: Default + 'static, R>(
+        cs: &ConstraintSystemRef<F>,
+        f: impl FnOnce(&mut T) -> R,
+    ) -> Option<R> {
+        let cache = cs.borrow()?.cache_map.clone();
+        let mut cache = cache.borrow_mut();
+        let entry = cache
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(T::default()));
+        entry.downcast_mut::<T>().map(f)
+    }
+}
+
+/// The linear combinations interned by [`AllocatedFp::cached_lc`], keyed by
+/// values of type `K`, stored in the cache map of a constraint system.
+struct LcCache<K>(BTreeMap<K, Variable>);
+
+impl<K> Default for LcCache<K> {
+    fn default() -> Self {
+        Self(BTreeMap::new())
+    }
 }
 
 /// Represent variables corresponding to a field element in `F`.
@@ -49,6 +123,14 @@ impl<F: PrimeField> FpVar<F> {
     /// that
     /// * `bits.len() == size`, and
     /// * `rest == 0`.
+    ///
+    /// This requires `size + 1` constraints. See
+    /// [`Self::to_bits_le_with_top_bits_zero_or_full`] for a variant that also
+    /// accepts `size == F::MODULUS_BIT_SIZE`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size >= F::MODULUS_BIT_SIZE`.
     pub fn to_bits_le_with_top_bits_zero(
         &self,
         size: usize,
@@ -67,11 +149,401 @@ impl<F: PrimeField> FpVar<F> {
                 Boolean::new_variable(cs.clone(), || value.map(|v| v.get_bit(i as usize)), mode)
             })
             .collect::<Result<Vec<_>, _>>()?;
+        if size == F::MODULUS_BIT_SIZE as usize - 1 {
+            if let Self::Var(v) = self {
+                // Any `size` bits represent an integer smaller than the
+                // modulus, so rather than computing `rest`, directly enforce
+                // `sum_i 2^i * lower_bits[i] == self` with a single
+                // constraint, as in `AllocatedFp::to_non_unique_bits_le`.
+                let lc = || {
+                    let mut coeff = F::one();
+                    let lc = lower_bits
+                        .iter()
+                        .map(|bit| {
+                            let c = coeff;
+                            coeff.double_in_place();
+                            (c, bit.variable())
+                        })
+                        .chain([(-F::ONE, v.variable)])
+                        .collect::<Vec<_>>();
+                    let mut lc = LinearCombination(lc);
+                    lc.compactify();
+                    lc
+                };
+                cs.enforce_r1cs_constraint(|| lc!(), || lc!(), lc)?;
+                return Ok((lower_bits, Self::zero()));
+            }
+        }
         let lower_bits_fp = Boolean::le_bits_to_fp(&lower_bits)?;
         let rest = self - &lower_bits_fp;
         rest.enforce_equal(&Self::zero())?;
         Ok((lower_bits, rest))
     }
+
+    /// Like [`Self::to_bits_le_with_top_bits_zero`], but also accepts
+    /// `size == F::MODULUS_BIT_SIZE`.
+    ///
+    /// Every field element fits into `F::MODULUS_BIT_SIZE` bits, so in that
+    /// case this outputs the canonical decomposition of `self` via
+    /// [`ToBitsGadget::to_bits_le`] instead of checking a remainder.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size > F::MODULUS_BIT_SIZE`.
+    pub fn to_bits_le_with_top_bits_zero_or_full(
+        &self,
+        size: usize,
+    ) -> Result<(Vec<Boolean<F>>, Self), SynthesisError> {
+        assert!(size <= F::MODULUS_BIT_SIZE as usize);
+        if size == F::MODULUS_BIT_SIZE as usize {
+            return Ok((self.to_bits_le()?, Self::zero()));
+        }
+        self.to_bits_le_with_top_bits_zero(size)
+    }
+
+    /// Packs the little-endian bytes `bytes` into a single `FpVar`.
+    ///
+    /// This relies on each `UInt8` already being constrained to a byte, so the
+    /// packing itself is a single linear combination. If `bytes` can represent
+    /// integers larger than the modulus, this additionally enforces that the
+    /// packed integer is smaller than the modulus.
+    ///
+    /// Returns an error if more than `ceil((F::MODULUS_BIT_SIZE - 1) / 8)`
+    /// bytes are provided.
+    #[tracing::instrument(target = "gr1cs", skip(bytes))]
+    pub fn from_bytes_le(bytes: &[UInt8<F>]) -> Result<Self, SynthesisError> {
+        let max_bytes = (F::MODULUS_BIT_SIZE as usize - 1).div_ceil(8);
+        if bytes.len() > max_bytes {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let bits = bytes.to_bits_le()?;
+        if bits.len() >= F::MODULUS_BIT_SIZE as usize {
+            Boolean::enforce_in_field_le(&bits)?;
+        }
+        Boolean::le_bits_to_fp(&bits)
+    }
+
+    /// Outputs `2^k * self`.
+    ///
+    /// This does not create any constraints.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn scale_by_power_of_two(&self, k: u32) -> Result<Self, SynthesisError> {
+        match self {
+            Self::Constant(c) => Ok(Self::Constant(*c * power_of_two::<F>(k))),
+            Self::Var(v) => v.scale_by_power_of_two(k).map(Self::Var),
+        }
+    }
+
+    /// Outputs `table[b[0] + 2 * b[1]]`, where the entries of `table` are
+    /// themselves variables.
+    ///
+    /// Unlike [`TwoBitLookupGadget::two_bit_lookup`], which folds a table of
+    /// constants into a single constraint, this is built from nested
+    /// conditional selections and requires three constraints.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `b.len() != 2` or `table.len() != 4`.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn two_bit_lookup_vars(b: &[Boolean<F>], table: &[Self]) -> Result<Self, SynthesisError> {
+        assert_eq!(b.len(), 2);
+        assert_eq!(table.len(), 4);
+        let low = b[0].select(&table[1], &table[0])?;
+        let high = b[0].select(&table[3], &table[2])?;
+        b[1].select(&high, &low)
+    }
+
+    /// Outputs `sum_i weights[i] * values[i]`, skipping the terms whose
+    /// weight is zero.
+    ///
+    /// Since the weights are known when the circuit is built, zero-weight
+    /// terms are dropped before building the linear combination, and constant
+    /// values are folded into a single constant term. This does not create any
+    /// constraints, and creates at most one linear combination.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weights` and `values` have different lengths.
+    #[tracing::instrument(target = "gr1cs", skip(weights, values))]
+    pub fn sparse_weighted_sum(weights: &[F], values: &[Self]) -> Result<Self, SynthesisError> {
+        let (constant, coeffs, variables) = Self::nonzero_terms(weights, values);
+        let sum = AllocatedFp::affine_combination(&coeffs, &variables, constant);
+        Ok(sum.map_or(Self::Constant(constant), Self::Var))
+    }
+
+    /// Outputs `sum_i c_i * v_i`, where `terms[i] = (c_i, v_i)`, as a single
+    /// linear combination created in `cs`.
+    ///
+    /// Constant terms are folded into a single constant, and terms with the
+    /// same underlying variable are combined, so the resulting linear
+    /// combination contains each variable at most once. This does not create
+    /// any constraints, and creates at most one linear combination; if no
+    /// term is a variable, the output is a constant.
+    ///
+    /// If `cs` is `ConstraintSystemRef::None`, the linear combination is
+    /// created in the constraint system of the variable terms.
+    #[tracing::instrument(target = "gr1cs", skip(terms))]
+    pub fn from_linear_combination(
+        cs: ConstraintSystemRef<F>,
+        terms: &[(F, Self)],
+    ) -> Result<Self, SynthesisError> {
+        let (weights, values): (Vec<_>, Vec<_>) = terms.iter().cloned().unzip();
+        let (constant, coeffs, variables) = Self::nonzero_terms(&weights, &values);
+        if variables.is_empty() {
+            return Ok(Self::Constant(constant));
+        }
+        let cs = variables.iter().fold(cs, |cs, v| cs.or(v.cs.clone()));
+        let value = coeffs
+            .iter()
+            .zip(&variables)
+            .try_fold(constant, |acc, (c, v)| Some(acc + *c * v.value?));
+        let variable = cs.new_lc(|| {
+            let mut lc = coeffs
+                .iter()
+                .zip(&variables)
+                .map(|(c, v)| (*c, v.variable))
+                .collect::<Vec<_>>();
+            if !constant.is_zero() {
+                lc.push((constant, Variable::One));
+            }
+            let mut lc = LinearCombination(lc);
+            // sorts and combines duplicate variables
+            lc.compactify();
+            lc
+        })?;
+        Ok(Self::Var(AllocatedFp::new(value, variable, cs)))
+    }
+
+    /// Outputs `sum_i c_i * v_i` over the pairs `(c_i, v_i)` yielded by
+    /// `iter`, as a single linear combination.
+    ///
+    /// This is the iterator counterpart of [`Self::from_linear_combination`]:
+    /// constant terms are folded into a single constant, and the remaining
+    /// terms are collected into one compactified linear combination, without
+    /// the intermediate linear combination for each `c_i * v_i`. An empty
+    /// iterator outputs zero.
+    #[tracing::instrument(target = "gr1cs", skip(iter))]
+    pub fn sum_with_coefficients<I: IntoIterator<Item = (F, Self)>>(iter: I) -> Self {
+        let terms = iter.into_iter().collect::<Vec<_>>();
+        Self::from_linear_combination(ConstraintSystemRef::None, &terms).unwrap()
+    }
+
+    /// Splits `sum_i weights[i] * values[i]` into a constant term and the
+    /// variable terms with non-zero weights.
+    fn nonzero_terms(weights: &[F], values: &[Self]) -> (F, Vec<F>, Vec<AllocatedFp<F>>) {
+        assert_eq!(weights.len(), values.len());
+        let mut constant = F::zero();
+        let mut coeffs = Vec::new();
+        let mut variables = Vec::new();
+        for (weight, value) in weights.iter().zip(values) {
+            if weight.is_zero() {
+                continue;
+            }
+            match value {
+                Self::Constant(c) => constant += *weight * c,
+                Self::Var(v) => {
+                    coeffs.push(*weight);
+                    variables.push(v.clone());
+                },
+            }
+        }
+        (constant, coeffs, variables)
+    }
+
+    /// Enforces that `self` is either zero or one, and outputs the
+    /// corresponding `Boolean`.
+    ///
+    /// The output is backed by the same variable as `self`, so this requires
+    /// a single constraint (`self * (self - 1) == 0`) and allocates nothing.
+    ///
+    /// Returns an error if `self` is a constant other than zero or one.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn enforce_bit(&self) -> Result<Boolean<F>, SynthesisError> {
+        match self {
+            Self::Constant(c) if c.is_zero() => Ok(Boolean::FALSE),
+            Self::Constant(c) if c.is_one() => Ok(Boolean::TRUE),
+            Self::Constant(_) => Err(SynthesisError::Unsatisfiable),
+            Self::Var(v) => {
+                v.cs.enforce_r1cs_constraint(
+                    || v.variable.into(),
+                    || lc_diff![v.variable, Variable::One],
+                    || lc!(),
+                )?;
+                Ok(Boolean::Var(
+                    AllocatedBool::new_variable_without_booleanity_check(
+                        v.variable,
+                        v.value.map(|value| value.is_one()),
+                        v.cs.clone(),
+                    ),
+                ))
+            },
+        }
+    }
+
+    /// Outputs the unique bit-wise decomposition of each element of `vars`
+    /// in *little-endian* form.
+    ///
+    /// This is equivalent to calling [`ToBitsGadget::to_bits_le`] on each
+    /// element, but computes the bound used by the canonicity check once for
+    /// the whole batch.
+    ///
+    /// The canonicity checks themselves cannot be batched: each one constrains
+    /// a different decomposition, and combining them (e.g. via a random linear
+    /// combination) would not rule out a single non-canonical decomposition.
+    /// Each element thus still requires its own reconstruction constraint and
+    /// its own invocation of `Boolean::enforce_smaller_or_equal_than_le`.
+    #[tracing::instrument(target = "gr1cs", skip(vars))]
+    pub fn batch_to_bits_le(vars: &[Self]) -> Result<Vec<Vec<Boolean<F>>>, SynthesisError> {
+        let mut modulus_minus_one = F::characteristic().to_vec();
+        // The characteristic is odd, so this does not borrow.
+        modulus_minus_one[0] -= 1;
+        vars.iter()
+            .map(|var| match var {
+                Self::Constant(_) => var.to_non_unique_bits_le(),
+                Self::Var(v) => {
+                    let bits = v.to_non_unique_bits_le()?;
+                    Boolean::enforce_smaller_or_equal_than_le(&bits, &modulus_minus_one)?;
+                    Ok(bits)
+                },
+            })
+            .collect()
+    }
+
+    /// Outputs, for each element of `vars`, the bit `vars[i] == 0`.
+    ///
+    /// Each non-constant element requires two constraints; constant elements
+    /// produce `Boolean::Constant` outputs and require no constraints.
+    #[tracing::instrument(target = "gr1cs", skip(vars))]
+    pub fn is_zero_batch(vars: &[Self]) -> Result<Vec<Boolean<F>>, SynthesisError> {
+        vars.iter().map(|var| var.is_zero()).collect()
+    }
+
+    /// Outputs the random linear combination
+    /// `values[0] + challenge * values[1] + ... + challenge^(n-1) *
+    /// values[n-1]`, computed via Horner's rule.
+    ///
+    /// For a random `challenge` chosen after `values` are fixed, two distinct
+    /// vectors fold to the same value only with negligible probability.
+    /// This requires `n - 1` constraints if `challenge` is not constant, and
+    /// none otherwise. The output is zero if `values` is empty.
+    #[tracing::instrument(target = "gr1cs", skip(values))]
+    pub fn fold_with_challenge(values: &[Self], challenge: &Self) -> Result<Self, SynthesisError> {
+        let mut values = values.iter().rev();
+        let mut acc = match values.next() {
+            Some(last) => last.clone(),
+            None => return Ok(Self::zero()),
+        };
+        for value in values {
+            acc = acc * challenge + value;
+        }
+        Ok(acc)
+    }
+
+    /// Outputs `base * sum_i 2^i * bits[i]`, i.e. the sum of the multiples
+    /// `2^i * base` selected by the little-endian `bits`.
+    ///
+    /// The packed bits form a linear combination, so this requires a single
+    /// constraint, and none if `base` or all of `bits` are constant.
+    #[tracing::instrument(target = "gr1cs", skip(bits))]
+    pub fn weighted_bit_sum(bits: &[Boolean<F>], base: &Self) -> Result<Self, SynthesisError> {
+        Ok(Boolean::le_bits_to_fp(bits)? * base)
+    }
+
+    /// Returns the value of `self`, or `None` if `self` is a variable without
+    /// an assigned value.
+    ///
+    /// See [`AllocatedFp::try_value`].
+    pub fn try_value(&self) -> Option<F> {
+        match self {
+            Self::Constant(c) => Some(*c),
+            Self::Var(v) => v.try_value(),
+        }
+    }
+
+    /// Returns the value of `self`, or `default` if `self` is a variable
+    /// without an assigned value.
+    pub fn value_or(&self, default: F) -> F {
+        self.try_value().unwrap_or(default)
+    }
+
+    /// Outputs `scale * self + shift`.
+    ///
+    /// This does not create any constraints, and creates at most one linear
+    /// combination, instead of one for the multiplication and one for the
+    /// addition.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn scale_and_shift(&self, scale: F, shift: F) -> Result<Self, SynthesisError> {
+        match self {
+            Self::Constant(c) => Ok(Self::Constant(*c * scale + shift)),
+            Self::Var(_) if scale.is_zero() => Ok(Self::Constant(shift)),
+            Self::Var(v) if scale.is_one() => Ok(Self::Var(v.add_constant(shift))),
+            Self::Var(v) if shift.is_zero() => Ok(Self::Var(v.mul_constant(scale))),
+            Self::Var(v) => {
+                let value = v.value.map(|val| val * scale + shift);
+                let lc = || lc![(scale, v.variable), (shift, Variable::One)];
+                let variable = v.cs.new_lc(lc)?;
+                Ok(Self::Var(AllocatedFp::new(value, variable, v.cs.clone())))
+            },
+        }
+    }
+
+    /// Enforces that `self * other == c`.
+    ///
+    /// This requires at most one constraint, and does not allocate `c`.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn mul_equals_constant(&self, other: &Self, c: F) -> Result<(), SynthesisError> {
+        match (self, other) {
+            (Self::Var(v1), Self::Var(v2)) => v1.mul_equals_constant(v2, c),
+            // this multiplication should be free
+            _ => (self * other).enforce_equal(&Self::Constant(c)),
+        }
+    }
+
+    /// Enforces that `self != 0`.
+    ///
+    /// This witnesses the inverse of `self` and enforces `self * inv == 1`,
+    /// which requires one constraint. If `self` is a constant, no constraints
+    /// are generated, and this fails with [`SynthesisError::Unsatisfiable`]
+    /// if `self` is zero.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn enforce_not_zero(&self) -> Result<(), SynthesisError> {
+        match self {
+            Self::Constant(c) if c.is_zero() => Err(SynthesisError::Unsatisfiable),
+            Self::Constant(_) => Ok(()),
+            Self::Var(v) => v.inverse().map(|_| ()),
+        }
+    }
+
+    /// Outputs a fresh witness variable equal to `self`.
+    ///
+    /// The output is a single variable rather than a (possibly large) linear
+    /// combination, which bounds the size of the linear combinations built
+    /// on top of it. If `self` is a variable, this requires one constraint;
+    /// constants are returned as is.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn as_fresh_witness(&self) -> Result<Self, SynthesisError> {
+        match self {
+            Self::Constant(_) => Ok(self.clone()),
+            Self::Var(v) => {
+                let fresh = AllocatedFp::new_witness(v.cs.clone(), || v.value.get())?;
+                v.cs.enforce_r1cs_constraint(
+                    || fresh.variable.into(),
+                    || Variable::One.into(),
+                    || v.variable.into(),
+                )?;
+                Ok(Self::Var(fresh))
+            },
+        }
+    }
+}
+
+/// Computes `2^k` by repeated doubling.
+fn power_of_two<F: PrimeField>(k: u32) -> F {
+    let mut result = F::ONE;
+    for _ in 0..k {
+        result.double_in_place();
+    }
+    result
 }
 
 impl<F: PrimeField> GR1CSVar<F> for FpVar<F> {
@@ -109,6 +581,16 @@ impl<F: PrimeField> From<Boolean<F>> for FpVar<F> {
     }
 }
 
+impl<F: PrimeField> TryFrom<FpVar<F>> for Boolean<F> {
+    type Error = SynthesisError;
+
+    /// Converts a boolean-valued `FpVar` into a `Boolean` backed by the same
+    /// variable, via [`FpVar::enforce_bit`].
+    fn try_from(other: FpVar<F>) -> Result<Self, Self::Error> {
+        other.enforce_bit()
+    }
+}
+
 impl<F: PrimeField> From<AllocatedFp<F>> for FpVar<F> {
     fn from(other: AllocatedFp<F>) -> Self {
         Self::Var(other)
@@ -133,6 +615,21 @@ impl<F: PrimeField> AllocatedFp<F> {
         self.value.ok_or(SynthesisError::AssignmentMissing)
     }
 
+    /// Returns the value assigned to `self`, or `None` if no value was
+    /// assigned.
+    ///
+    /// Unlike [`Self::value`], this does not error on partial assignments,
+    /// which is useful for tooling that inspects a circuit.
+    pub fn try_value(&self) -> Option<F> {
+        self.value
+    }
+
+    /// Returns the value assigned to `self`, or `default` if no value was
+    /// assigned.
+    pub fn value_or(&self, default: F) -> F {
+        self.value.unwrap_or(default)
+    }
+
     /// Outputs `self + other`.
     ///
     /// This does not create any constraints.
@@ -205,6 +702,25 @@ impl<F: PrimeField> AllocatedFp<F> {
     ///
     /// Panics if the iterators are of different lengths.
     pub fn linear_combination<B1, B2, I1>(this: I1, other: &[B2]) -> Option<Self>
+    where
+        B1: Borrow<F>,
+        B2: Borrow<Self>,
+        I1: IntoIterator<Item = B1, IntoIter: Clone>,
+    {
+        Self::affine_combination(this, other, F::zero())
+    }
+
+    /// Computes `constant + Σ this_i * other_i`, where `this` is an iterator
+    /// of coefficients.
+    ///
+    /// This does not create any constraints and only creates one linear
+    /// combination. Returns `None` if there are no variables, since there is
+    /// then no constraint system to create the linear combination in.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterators are of different lengths.
+    pub fn affine_combination<B1, B2, I1>(this: I1, other: &[B2], constant: F) -> Option<Self>
     where
         B1: Borrow<F>,
         B2: Borrow<Self>,
@@ -212,7 +728,7 @@ impl<F: PrimeField> AllocatedFp<F> {
     {
         let mut cs = ConstraintSystemRef::None;
         let mut has_value = true;
-        let mut value = F::zero();
+        let mut value = constant;
 
         let mut num_iters = 0;
         let zipped = zip_eq(this, other);
@@ -233,9 +749,12 @@ impl<F: PrimeField> AllocatedFp<F> {
 
         let variable = cs
             .new_lc(|| {
-                let lc = zipped
+                let mut lc = zipped
                     .map(|(coeff, variable)| (*coeff.borrow(), variable.borrow().variable))
                     .collect::<Vec<_>>();
+                if !constant.is_zero() {
+                    lc.push((constant, Variable::One));
+                }
                 let mut lc = LinearCombination(lc);
                 // sorts and compacts
                 lc.compactify();
@@ -340,9 +859,24 @@ impl<F: PrimeField> AllocatedFp<F> {
 
     /// Outputs `self * other`.
     ///
-    /// This requires *one* constraint.
+    /// This requires *one* constraint, unless one of the operands is the
+    /// constant-system variable `Variable::One` or `Variable::Zero`, which
+    /// [`AllocVar::new_constant`] outputs for the constants one and zero. These
+    /// are fixed by the constraint system itself, so the product can be folded
+    /// into a linear combination for free. This is only done for such
+    /// genuinely-constant variables; an operand that merely has the value zero
+    /// or one in the current assignment still requires a constraint.
     #[tracing::instrument(target = "gr1cs")]
     pub fn mul(&self, other: &Self) -> Self {
+        match (self.variable, other.variable) {
+            (Variable::Zero, _) | (_, Variable::Zero) => {
+                let cs = self.cs.clone().or(other.cs.clone());
+                return AllocatedFp::new(Some(F::zero()), Variable::Zero, cs);
+            },
+            (Variable::One, _) => return other.clone(),
+            (_, Variable::One) => return self.clone(),
+            _ => {},
+        }
         let product = AllocatedFp::new_witness(self.cs.clone(), || {
             Ok(self.value.get()? * &other.value.get()?)
         })
@@ -357,6 +891,33 @@ impl<F: PrimeField> AllocatedFp<F> {
         product
     }
 
+    /// Enforces that `self * other = result`, without allocating a new
+    /// variable.
+    ///
+    /// This is the same as [`Self::mul_equals`], and requires *one*
+    /// constraint.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn mul_into(&self, other: &Self, result: &Self) -> Result<(), SynthesisError> {
+        self.mul_equals(other, result)
+    }
+
+    /// Outputs `self * other`, reusing `candidate` as the product if one is
+    /// supplied.
+    ///
+    /// If `candidate` is `Some`, this enforces that it equals `self * other`
+    /// and returns it, without allocating a new variable. Otherwise, this
+    /// behaves like [`Self::mul`]. Either way, this requires *one* constraint.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn mul_reuse(&self, other: &Self, candidate: Option<Self>) -> Self {
+        match candidate {
+            Some(product) => {
+                self.mul_equals(other, &product).unwrap();
+                product
+            },
+            None => self.mul(other),
+        }
+    }
+
     /// Output `self + other`
     ///
     /// This does not create any constraints.
@@ -406,6 +967,17 @@ impl<F: PrimeField> AllocatedFp<F> {
         Ok(Self::new(value, variable, self.cs.clone()))
     }
 
+    /// Output `2^k * self`
+    ///
+    /// This does not create any constraints.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn scale_by_power_of_two(&self, k: u32) -> Result<Self, SynthesisError> {
+        let coeff = power_of_two::<F>(k);
+        let value = self.value.map(|val| val * coeff);
+        let variable = self.cs.new_lc(|| (coeff, self.variable).into())?;
+        Ok(Self::new(value, variable, self.cs.clone()))
+    }
+
     /// Output `-self`
     ///
     /// This does not create any constraints.
@@ -483,6 +1055,30 @@ impl<F: PrimeField> AllocatedFp<F> {
         )
     }
 
+    /// Enforces that `self * other = c`.
+    ///
+    /// This requires *one* constraint, and unlike [`Self::mul_equals`], uses
+    /// `c` directly in the constraint instead of allocating it.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn mul_equals_constant(&self, other: &Self, c: F) -> Result<(), SynthesisError> {
+        self.cs.enforce_r1cs_constraint(
+            || self.variable.into(),
+            || other.variable.into(),
+            || (c, Variable::One).into(),
+        )
+    }
+
+    /// Outputs a variable for the linear combination `self - other`.
+    ///
+    /// The variable is cached per ordered pair `(self, other)`, so that
+    /// `is_eq`, `is_neq`, `conditional_enforce_equal` and
+    /// `conditional_enforce_not_equal` on the same pair share a single
+    /// linear combination.
+    fn difference_lc(&self, other: &Self) -> Result<Variable, SynthesisError> {
+        let key = (self.variable, other.variable);
+        Self::cached_lc(&self.cs, key, || lc_diff![self.variable, other.variable])
+    }
+
     /// Outputs the bit `self == other`.
     ///
     /// This requires two constraints.
@@ -556,7 +1152,7 @@ impl<F: PrimeField> AllocatedFp<F> {
         // and constraint 2 enforces that if self != other, then `is_not_equal = 1`.
         // Since these are the only possible two cases, `is_not_equal` is always
         // constrained to 0 or 1.
-        let difference = self.cs.new_lc(|| lc_diff![self.variable, other.variable])?;
+        let difference = self.difference_lc(other)?;
         self.cs.enforce_r1cs_constraint(
             || difference.into(),
             || multiplier.into(),
@@ -577,11 +1173,9 @@ impl<F: PrimeField> AllocatedFp<F> {
         other: &Self,
         should_enforce: &Boolean<F>,
     ) -> Result<(), SynthesisError> {
-        self.cs.enforce_r1cs_constraint(
-            || lc_diff![self.variable, other.variable],
-            || should_enforce.lc(),
-            || lc!(),
-        )
+        let difference = self.difference_lc(other)?;
+        self.cs
+            .enforce_r1cs_constraint(|| difference.into(), || should_enforce.lc(), || lc!())
     }
 
     /// Enforces that self != other if `should_enforce.is_eq(&Boolean::TRUE)`.
@@ -612,8 +1206,9 @@ impl<F: PrimeField> AllocatedFp<F> {
             }
         })?;
 
+        let difference = self.difference_lc(other)?;
         self.cs.enforce_r1cs_constraint(
-            || lc_diff![self.variable, other.variable],
+            || difference.into(),
             || multiplier.variable.into(),
             || should_enforce.lc(),
         )?;
@@ -834,8 +1429,19 @@ impl<F: PrimeField> AllocVar<F, F> for AllocatedFp<F> {
         let ns = cs.into();
         let cs = ns.cs();
         if mode == AllocationMode::Constant {
+            // Zero and one are represented by the constant-system variables,
+            // which lets `AllocatedFp::mul` fold products with them. Other
+            // constants get a fresh linear combination `(v, One)` on every
+            // call; see `AllocatedFp::new_constant_cached` for a variant that
+            // reuses it.
             let v = *f()?.borrow();
-            let lc = cs.new_lc(|| (v, Variable::One).into())?;
+            let lc = if v.is_zero() {
+                Variable::Zero
+            } else if v.is_one() {
+                Variable::One
+            } else {
+                cs.new_lc(|| (v, Variable::One).into())?
+            };
             Ok(Self::new(Some(v), lc, cs))
         } else {
             let mut value = None;
@@ -900,11 +1506,7 @@ impl<F: PrimeField> FieldVar<F, F> for FpVar<F> {
                 result.enforce_equal(&(self * other))
             }, // this multiplication should be free
             (Var(v1), Var(v2), Var(v3)) => v1.mul_equals(v2, v3),
-            (Var(v1), Var(v2), Constant(f)) => {
-                let cs = v1.cs.clone();
-                let v3 = AllocatedFp::new_constant(cs, f).unwrap();
-                v1.mul_equals(v2, &v3)
-            },
+            (Var(v1), Var(v2), Constant(f)) => v1.mul_equals_constant(v2, *f),
         }
     }
 
@@ -991,9 +1593,9 @@ impl<F: PrimeField> FieldVar<F, F> for FpVar<F> {
         }
     }
 
+    /// This is a no-op for prime fields.
     #[tracing::instrument(target = "gr1cs")]
-    fn frobenius_map_in_place(&mut self, power: usize) -> Result<&mut Self, SynthesisError> {
-        *self = self.frobenius_map(power)?;
+    fn frobenius_map_in_place(&mut self, _: usize) -> Result<&mut Self, SynthesisError> {
         Ok(self)
     }
 }
@@ -1013,7 +1615,13 @@ impl_ops!(
             (Var(v1), Var(v2)) => Var(v1.add(v2)),
         }
     },
-    |this: &'a FpVar<F>, other: F| { this + &FpVar::Constant(other) },
+    |this: &'a FpVar<F>, other: F| {
+        if other.is_zero() {
+            this.clone()
+        } else {
+            this + &FpVar::Constant(other)
+        }
+    },
     F: PrimeField,
 );
 
@@ -1033,7 +1641,13 @@ impl_ops!(
             (Var(v1), Var(v2)) => Var(v1.sub(v2)),
         }
     },
-    |this: &'a FpVar<F>, other: F| { this - &FpVar::Constant(other) },
+    |this: &'a FpVar<F>, other: F| {
+        if other.is_zero() {
+            this.clone()
+        } else {
+            this - &FpVar::Constant(other)
+        }
+    },
     F: PrimeField
 );
 
@@ -1055,6 +1669,8 @@ impl_ops!(
     |this: &'a FpVar<F>, other: F| {
         if other.is_zero() {
             FpVar::zero()
+        } else if other.is_one() {
+            this.clone()
         } else {
             this * &FpVar::Constant(other)
         }
@@ -1086,7 +1702,17 @@ impl<F: PrimeField> EqGadget<F> for FpVar<F> {
         should_enforce: &Boolean<F>,
     ) -> Result<(), SynthesisError> {
         match (self, other) {
-            (Self::Constant(_), Self::Constant(_)) => Ok(()),
+            (Self::Constant(c1), Self::Constant(c2)) => {
+                if c1 == c2 {
+                    Ok(())
+                } else {
+                    // The check can only hold if `should_enforce` is false. If
+                    // `should_enforce` is a constant, this is resolved without
+                    // generating constraints, and fails immediately if it is
+                    // `true`.
+                    should_enforce.enforce_equal(&Boolean::FALSE)
+                }
+            },
             (Self::Constant(c), Self::Var(v)) | (Self::Var(v), Self::Constant(c)) => {
                 let cs = v.cs.clone();
                 let c = AllocatedFp::new_constant(cs, c)?;
@@ -1180,10 +1806,12 @@ impl<F: PrimeField> CondSelectGadget<F> for FpVar<F> {
             _ => {
                 match (true_value, false_value) {
                     (Self::Constant(t), Self::Constant(f)) => {
-                        let is = AllocatedFp::from(cond.clone());
-                        let not = AllocatedFp::from(!cond);
-                        // cond * t + (1 - cond) * f
-                        Ok(is.mul_constant(*t).add(&not.mul_constant(*f)).into())
+                        // cond * t + (1 - cond) * f = f + cond * (t - f), which
+                        // can be expressed as a single linear combination.
+                        let cs = cond.cs();
+                        let value = cond.value().ok().map(|c| if c { *t } else { *f });
+                        let variable = cs.new_lc(|| cond.lc() * (*t - *f) + (*f, Variable::One))?;
+                        Ok(AllocatedFp::new(value, variable, cs).into())
                     },
                     (..) => {
                         let cs = cond.cs();
@@ -1316,15 +1944,21 @@ impl<'a, F: PrimeField> Sum<FpVar<F>> for FpVar<F> {
 
 #[cfg(test)]
 mod test {
+    use super::AllocatedFp;
     use crate::{
-        alloc::AllocVar,
+        alloc::{AllocVar, AllocationMode},
+        boolean::Boolean,
+        convert::{ToBitsGadget, ToBytesGadget},
         eq::EqGadget,
         fields::{fp::FpVar, FieldVar},
-        test_utils::{combination, modes},
+        select::ThreeBitCondNegLookupGadget,
+        test_utils::{combination, modes, num_lcs},
+        uint8::UInt8,
         GR1CSVar,
     };
-    use ark_relations::gr1cs::ConstraintSystem;
-    use ark_std::{UniformRand, Zero};
+    use ark_ff::{BigInteger, Field, PrimeField};
+    use ark_relations::gr1cs::{ConstraintSystem, SynthesisError, Variable};
+    use ark_std::{One, UniformRand, Zero};
     use ark_test_curves::bls12_381::Fr;
 
     #[test]
@@ -1380,4 +2014,820 @@ mod test {
             assert_eq!(sum.value().unwrap(), sum_expected);
         }
     }
+
+    #[test]
+    fn test_conditional_enforce_equal_constants() {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let one = FpVar::constant(Fr::from(1u64));
+        let two = FpVar::constant(Fr::from(2u64));
+
+        // Equal constants never need constraints.
+        one.conditional_enforce_equal(&one, &Boolean::TRUE).unwrap();
+        // Unequal constants are fine as long as the check is disabled.
+        one.conditional_enforce_equal(&two, &Boolean::FALSE)
+            .unwrap();
+        assert_eq!(cs.num_constraints(), 0);
+
+        // Unequal constants with an enabled check fail at synthesis time.
+        assert!(matches!(
+            one.conditional_enforce_equal(&two, &Boolean::TRUE),
+            Err(SynthesisError::Unsatisfiable)
+        ));
+
+        // With a variable condition, the condition is forced to be false.
+        let condition = Boolean::new_witness(cs.clone(), || Ok(true)).unwrap();
+        one.conditional_enforce_equal(&two, &condition).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_from_bytes_le() {
+        let mut rng = ark_std::test_rng();
+        for _ in 0..10 {
+            let cs = ConstraintSystem::new_ref();
+            let a = Fr::rand(&mut rng);
+            let a_var = FpVar::new_witness(cs.clone(), || Ok(a)).unwrap();
+            let bytes = a_var.to_bytes_le().unwrap();
+            let packed = FpVar::from_bytes_le(&bytes).unwrap();
+            assert_eq!(packed.value().unwrap(), a);
+            packed.enforce_equal(&a_var).unwrap();
+            assert!(cs.is_satisfied().unwrap());
+        }
+
+        // `max_bytes` bytes can exceed the modulus, so they must be checked
+        // for canonicity.
+        let max_bytes = (Fr::MODULUS_BIT_SIZE as usize - 1).div_ceil(8);
+        let minus_one = (-Fr::one()).into_bigint().to_bytes_le();
+        let modulus = Fr::MODULUS.to_bytes_le();
+        for (bytes, expected) in [(minus_one, true), (modulus, false)] {
+            let cs = ConstraintSystem::new_ref();
+            assert_eq!(bytes.len(), max_bytes);
+            let bytes = UInt8::new_witness_vec(cs.clone(), &bytes).unwrap();
+            let packed = FpVar::<Fr>::from_bytes_le(&bytes).unwrap();
+            if expected {
+                assert_eq!(packed.value().unwrap(), -Fr::one());
+            }
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+
+        for len in [max_bytes + 1, 64] {
+            let too_many = UInt8::constant_vec(&vec![0u8; len]);
+            assert!(matches!(
+                FpVar::<Fr>::from_bytes_le(&too_many),
+                Err(SynthesisError::Unsatisfiable)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_two_bit_lookup_vars() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::new_ref();
+        let values = [(); 4].map(|_| Fr::rand(&mut rng));
+        let table = values.map(|v| FpVar::new_witness(cs.clone(), || Ok(v)).unwrap());
+        for index in 0..4 {
+            let b = [index & 1 == 1, index & 2 == 2]
+                .map(|bit| Boolean::new_witness(cs.clone(), || Ok(bit)).unwrap());
+            let result = FpVar::two_bit_lookup_vars(&b, &table).unwrap();
+            assert_eq!(result.value().unwrap(), values[index]);
+        }
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_scale_by_power_of_two() {
+        let mut rng = ark_std::test_rng();
+        for mode in modes() {
+            let cs = ConstraintSystem::new_ref();
+            let a = Fr::rand(&mut rng);
+            let a_var = FpVar::new_variable(cs.clone(), || Ok(a), mode).unwrap();
+            for k in [0u32, 1, 7, 64, 200] {
+                let scaled = a_var.scale_by_power_of_two(k).unwrap();
+                assert_eq!(scaled.value().unwrap(), a * Fr::from(2u64).pow([k as u64]));
+            }
+            assert_eq!(cs.num_constraints(), 0);
+        }
+    }
+
+    #[test]
+    fn test_frobenius_map_in_place_is_no_op() {
+        let mut rng = ark_std::test_rng();
+        for mode in modes() {
+            let cs = ConstraintSystem::new_ref();
+            let a = Fr::rand(&mut rng);
+            let mut a_var = FpVar::new_variable(cs.clone(), || Ok(a), mode).unwrap();
+            let variable = match &a_var {
+                FpVar::Var(v) => Some(v.variable),
+                FpVar::Constant(_) => None,
+            };
+            let num_constraints = cs.num_constraints();
+            let num_witnesses = cs.num_witness_variables();
+
+            for power in 0..4 {
+                a_var.frobenius_map_in_place(power).unwrap();
+            }
+            assert_eq!(a_var.value().unwrap(), a);
+            assert_eq!(cs.num_constraints(), num_constraints);
+            assert_eq!(cs.num_witness_variables(), num_witnesses);
+            if let FpVar::Var(v) = &a_var {
+                assert_eq!(Some(v.variable), variable);
+            }
+        }
+    }
+
+    #[test]
+    fn test_affine_combination() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::new_ref();
+        let coeffs = (0..5).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let values = (0..5).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let vars = values
+            .iter()
+            .map(|v| AllocatedFp::new_witness(cs.clone(), || Ok(v)).unwrap())
+            .collect::<Vec<_>>();
+        let constant = Fr::rand(&mut rng);
+
+        let lcs_before = num_lcs(&cs);
+        let result = AllocatedFp::affine_combination(&coeffs, &vars, constant).unwrap();
+        let expected = constant + coeffs.iter().zip(&values).map(|(c, v)| *c * v).sum::<Fr>();
+        assert_eq!(result.value().unwrap(), expected);
+        assert_eq!(num_lcs(&cs), lcs_before + 1);
+        assert_eq!(cs.num_constraints(), 0);
+
+        assert!(AllocatedFp::<Fr>::affine_combination(
+            &[] as &[Fr],
+            &[] as &[AllocatedFp<Fr>],
+            Fr::zero()
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_mul_reuse() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::new_ref();
+        let (a, b) = (Fr::rand(&mut rng), Fr::rand(&mut rng));
+        let a_var = AllocatedFp::new_witness(cs.clone(), || Ok(a)).unwrap();
+        let b_var = AllocatedFp::new_witness(cs.clone(), || Ok(b)).unwrap();
+        let candidate = AllocatedFp::new_witness(cs.clone(), || Ok(a * b)).unwrap();
+
+        let num_witnesses = cs.num_witness_variables();
+        let product = a_var.mul_reuse(&b_var, Some(candidate.clone()));
+        assert_eq!(cs.num_witness_variables(), num_witnesses);
+        assert_eq!(product.variable, candidate.variable);
+        a_var.mul_into(&b_var, &candidate).unwrap();
+        assert_eq!(cs.num_witness_variables(), num_witnesses);
+        assert!(cs.is_satisfied().unwrap());
+
+        let product = a_var.mul_reuse(&b_var, None);
+        assert_eq!(cs.num_witness_variables(), num_witnesses + 1);
+        assert_eq!(product.value().unwrap(), a * b);
+
+        let wrong = AllocatedFp::new_witness(cs.clone(), || Ok(a + b)).unwrap();
+        let _ = a_var.mul_reuse(&b_var, Some(wrong));
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_is_eq_and_conditional_enforce_equal() {
+        let mut rng = ark_std::test_rng();
+        for equal in [true, false] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let a = Fr::rand(&mut rng);
+            let b = if equal { a } else { Fr::rand(&mut rng) };
+            let a_var = AllocatedFp::new_witness(cs.clone(), || Ok(a)).unwrap();
+            let b_var = AllocatedFp::new_witness(cs.clone(), || Ok(b)).unwrap();
+
+            let lcs = num_lcs(&cs);
+            let (num_witnesses, num_constraints) =
+                (cs.num_witness_variables(), cs.num_constraints());
+            let is_eq = a_var.is_eq(&b_var).unwrap();
+            a_var.conditional_enforce_equal(&b_var, &is_eq).unwrap();
+            assert_eq!(is_eq.value().unwrap(), equal);
+            // `is_eq` allocates the result bit and the inverse of the
+            // difference; `conditional_enforce_equal` allocates nothing.
+            assert_eq!(cs.num_witness_variables(), num_witnesses + 2);
+            assert_eq!(cs.num_constraints(), num_constraints + 3);
+            // The difference, and the two negations of the result bit in
+            // `is_eq`. `conditional_enforce_equal` reuses the difference.
+            assert_eq!(num_lcs(&cs), lcs + 3);
+
+            // Later checks on the same pair also reuse the difference.
+            let is_neq = a_var.is_neq(&b_var).unwrap();
+            a_var
+                .conditional_enforce_not_equal(&b_var, &is_neq)
+                .unwrap();
+            assert_eq!(num_lcs(&cs), lcs + 4);
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_enforce_bit() {
+        for (value, expected) in [(0u64, Some(false)), (1, Some(true)), (2, None)] {
+            let value = Fr::from(value);
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let var = FpVar::new_witness(cs.clone(), || Ok(value)).unwrap();
+            let num_witnesses = cs.num_witness_variables();
+            let bit = var.enforce_bit().unwrap();
+            assert_eq!(cs.num_witness_variables(), num_witnesses);
+            assert_eq!(cs.num_constraints(), 1);
+            assert_eq!(cs.is_satisfied().unwrap(), expected.is_some());
+            if let Some(expected) = expected {
+                assert_eq!(bit.value().unwrap(), expected);
+                // The bit is backed by the same variable as `var`.
+                FpVar::from(bit).enforce_equal(&var).unwrap();
+                assert!(cs.is_satisfied().unwrap());
+            }
+
+            let constant = FpVar::Constant(value).enforce_bit();
+            match expected {
+                Some(expected) => assert_eq!(constant.unwrap(), Boolean::constant(expected)),
+                None => assert!(constant.is_err()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_try_from_fpvar_for_boolean() {
+        let zero = Boolean::try_from(FpVar::Constant(Fr::zero())).unwrap();
+        assert_eq!(zero, Boolean::FALSE);
+        let one = Boolean::try_from(FpVar::Constant(Fr::one())).unwrap();
+        assert_eq!(one, Boolean::TRUE);
+        assert_eq!(
+            Boolean::try_from(FpVar::Constant(Fr::from(2u64))),
+            Err(SynthesisError::Unsatisfiable)
+        );
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let var = FpVar::new_witness(cs.clone(), || Ok(Fr::one())).unwrap();
+        let bit = Boolean::try_from(var.clone()).unwrap();
+        assert!(bit.value().unwrap());
+        assert_eq!(cs.num_constraints(), 1);
+        // The bit is backed by the same variable as `var`.
+        FpVar::from(bit).enforce_equal(&var).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_sparse_weighted_sum() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let mut weights = Vec::new();
+        let mut values = Vec::new();
+        let mut expected = Fr::zero();
+        for i in 0..32 {
+            let weight = if i % 5 == 0 {
+                Fr::rand(&mut rng)
+            } else {
+                Fr::zero()
+            };
+            let value = Fr::rand(&mut rng);
+            expected += weight * value;
+            weights.push(weight);
+            values.push(if i % 10 == 5 {
+                FpVar::Constant(value)
+            } else {
+                FpVar::new_witness(cs.clone(), || Ok(value)).unwrap()
+            });
+        }
+
+        // Only the witnesses with non-zero weights (at indices 0, 10, 20 and
+        // 30) are part of the linear combination; the constants at indices 5,
+        // 15 and 25 are folded into the constant term.
+        let (constant, coeffs, variables) = FpVar::nonzero_terms(&weights, &values);
+        let expected_constant = [5, 15, 25]
+            .iter()
+            .map(|&i| weights[i] * values[i].value().unwrap())
+            .sum::<Fr>();
+        assert_eq!(constant, expected_constant);
+        assert_eq!(coeffs.len(), 4);
+        assert_eq!(variables.len(), 4);
+
+        let lcs_before = num_lcs(&cs);
+        let sum = FpVar::sparse_weighted_sum(&weights, &values).unwrap();
+        assert_eq!(num_lcs(&cs), lcs_before + 1);
+        assert_eq!(sum.value().unwrap(), expected);
+        assert_eq!(cs.num_constraints(), 0);
+
+        let weight_vars = weights
+            .iter()
+            .map(|w| FpVar::Constant(*w))
+            .collect::<Vec<_>>();
+        let dense = FpVar::inner_product(&weight_vars, &values).unwrap();
+        assert_eq!(dense.value().unwrap(), expected);
+
+        let zeros = vec![Fr::zero(); values.len()];
+        let sum = FpVar::sparse_weighted_sum(&zeros, &values).unwrap();
+        assert!(sum.is_constant());
+        assert_eq!(sum.value().unwrap(), Fr::zero());
+    }
+
+    #[test]
+    fn test_batch_to_bits_le() {
+        let mut rng = ark_std::test_rng();
+        let values = (0..8).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let vars = values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                if i == 3 {
+                    FpVar::Constant(*v)
+                } else {
+                    FpVar::new_witness(cs.clone(), || Ok(*v)).unwrap()
+                }
+            })
+            .collect::<Vec<_>>();
+        let batch = FpVar::batch_to_bits_le(&vars).unwrap();
+        assert_eq!(batch.len(), vars.len());
+        for ((bits, var), value) in batch.iter().zip(&vars).zip(&values) {
+            let individual = var.to_bits_le().unwrap();
+            assert_eq!(bits.value().unwrap(), individual.value().unwrap());
+            let packed = Boolean::le_bits_to_fp(bits).unwrap();
+            assert_eq!(packed.value().unwrap(), *value);
+        }
+        assert!(cs.is_satisfied().unwrap());
+
+        // The batch costs as much as decomposing each element separately.
+        let other_cs = ConstraintSystem::<Fr>::new_ref();
+        for var in &vars {
+            let var = match var {
+                FpVar::Constant(_) => var.clone(),
+                FpVar::Var(_) => FpVar::new_witness(other_cs.clone(), || var.value()).unwrap(),
+            };
+            let _ = var.to_bits_le().unwrap();
+        }
+        // `cs` additionally contains the decompositions computed above.
+        let num_individual = other_cs.num_constraints();
+        assert_eq!(cs.num_constraints(), 2 * num_individual);
+    }
+
+    #[test]
+    fn test_is_zero_batch() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let nonzero = Fr::rand(&mut rng);
+        let vars = [
+            FpVar::new_witness(cs.clone(), || Ok(Fr::zero())).unwrap(),
+            FpVar::Constant(nonzero),
+            FpVar::new_witness(cs.clone(), || Ok(nonzero)).unwrap(),
+            FpVar::Constant(Fr::zero()),
+            FpVar::new_input(cs.clone(), || Ok(Fr::zero())).unwrap(),
+        ];
+        let is_zero = FpVar::is_zero_batch(&vars).unwrap();
+        assert_eq!(
+            is_zero.value().unwrap(),
+            vec![true, false, false, true, true]
+        );
+        assert_eq!(is_zero[1], Boolean::FALSE);
+        assert_eq!(is_zero[3], Boolean::TRUE);
+        assert_eq!(cs.num_constraints(), 6);
+        assert!(cs.is_satisfied().unwrap());
+
+        assert!(FpVar::<Fr>::is_zero_batch(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_alloc_array() {
+        let mut rng = ark_std::test_rng();
+        let values = [(); 3].map(|_| Fr::rand(&mut rng));
+        for mode in modes() {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let vars = <[FpVar<Fr>; 3]>::new_variable(cs.clone(), || Ok(values), mode).unwrap();
+            assert_eq!(vars.value().unwrap(), values);
+            for var in &vars {
+                assert_eq!(var.is_constant(), mode == AllocationMode::Constant);
+            }
+            let is_witness = mode == AllocationMode::Witness;
+            assert_eq!(cs.num_witness_variables(), if is_witness { 3 } else { 0 });
+        }
+    }
+
+    #[test]
+    fn test_constant_identities() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let x = FpVar::new_witness(cs.clone(), || Ok(Fr::rand(&mut rng))).unwrap();
+        let lcs_before = num_lcs(&cs);
+        let witnesses_before = cs.num_witness_variables();
+        let results = [
+            &x + Fr::zero(),
+            &x - Fr::zero(),
+            &x * Fr::one(),
+            x.clone() + Fr::zero(),
+            x.clone() - Fr::zero(),
+            x.clone() * Fr::one(),
+        ];
+        for result in results {
+            assert_eq!(result.value().unwrap(), x.value().unwrap());
+        }
+        let mut y = x.clone();
+        y += Fr::zero();
+        y -= Fr::zero();
+        y *= Fr::one();
+        assert_eq!(y.value().unwrap(), x.value().unwrap());
+        assert_eq!(num_lcs(&cs), lcs_before);
+        assert_eq!(cs.num_witness_variables(), witnesses_before);
+        assert_eq!(cs.num_constraints(), 0);
+    }
+
+    #[test]
+    fn test_allocated_mul_by_constant_variable() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let x = AllocatedFp::new_witness(cs.clone(), || Ok(Fr::rand(&mut rng))).unwrap();
+        let one = AllocatedFp::new_constant(cs.clone(), Fr::one()).unwrap();
+        let zero = AllocatedFp::new_constant(cs.clone(), Fr::zero()).unwrap();
+        assert_eq!(one.variable, Variable::One);
+        assert_eq!(zero.variable, Variable::Zero);
+        let witnesses_before = cs.num_witness_variables();
+
+        for product in [x.mul(&one), one.mul(&x)] {
+            assert_eq!(product.variable, x.variable);
+            assert_eq!(product.value, x.value);
+        }
+        for product in [x.mul(&zero), zero.mul(&x), zero.mul(&one)] {
+            assert_eq!(product.variable, Variable::Zero);
+            assert_eq!(product.value, Some(Fr::zero()));
+        }
+        assert_eq!(cs.num_constraints(), 0);
+        assert_eq!(cs.num_witness_variables(), witnesses_before);
+
+        // A witness that happens to equal one still requires a constraint.
+        let witness_one = AllocatedFp::new_witness(cs.clone(), || Ok(Fr::one())).unwrap();
+        let product = x.mul(&witness_one);
+        assert_eq!(product.value, x.value);
+        assert_eq!(cs.num_constraints(), 1);
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_fold_with_challenge() {
+        let mut rng = ark_std::test_rng();
+        let challenge = Fr::rand(&mut rng);
+        let values = (0..5).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let mut other = values.clone();
+        other[2] += Fr::one();
+        let native = |values: &[Fr]| {
+            let mut power = Fr::one();
+            let mut sum = Fr::zero();
+            for value in values {
+                sum += power * value;
+                power *= challenge;
+            }
+            sum
+        };
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let alloc = |values: &[Fr]| {
+            values
+                .iter()
+                .map(|v| FpVar::new_witness(cs.clone(), || Ok(*v)).unwrap())
+                .collect::<Vec<_>>()
+        };
+        let (vars, other_vars) = (alloc(&values), alloc(&other));
+        let challenge_var = FpVar::new_input(cs.clone(), || Ok(challenge)).unwrap();
+        let folded = FpVar::fold_with_challenge(&vars, &challenge_var).unwrap();
+        let other_folded = FpVar::fold_with_challenge(&other_vars, &challenge_var).unwrap();
+        assert_eq!(folded.value().unwrap(), native(&values));
+        assert_eq!(other_folded.value().unwrap(), native(&other));
+        assert_ne!(folded.value().unwrap(), other_folded.value().unwrap());
+        assert_eq!(cs.num_constraints(), 2 * (values.len() - 1));
+        assert!(cs.is_satisfied().unwrap());
+
+        // Constant inputs collapse to a constant, and an empty vector folds
+        // to zero.
+        let constants: Vec<_> = values.iter().map(|v| FpVar::Constant(*v)).collect();
+        let folded = FpVar::fold_with_challenge(&constants, &FpVar::Constant(challenge)).unwrap();
+        assert!(folded.is_constant());
+        assert_eq!(folded.value().unwrap(), native(&values));
+        let empty = FpVar::fold_with_challenge(&[], &challenge_var).unwrap();
+        assert!(empty.is_constant());
+        assert_eq!(empty.value().unwrap(), Fr::zero());
+    }
+
+    #[test]
+    fn test_to_bits_le_with_top_bits_zero_sizes() {
+        let mut rng = ark_std::test_rng();
+        let full_size = Fr::MODULUS_BIT_SIZE as usize;
+        let value = Fr::rand(&mut rng);
+        for size in [8, full_size - 2, full_size - 1, full_size] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            // Make sure that `value` fits into `size` bits.
+            let mut bigint = value.into_bigint();
+            for i in size..full_size {
+                bigint.set_bit(i, false);
+            }
+            let value = Fr::from_bigint(bigint).unwrap();
+            let var = FpVar::new_witness(cs.clone(), || Ok(value)).unwrap();
+            let before = cs.num_constraints();
+            let (bits, rest) = var.to_bits_le_with_top_bits_zero_or_full(size).unwrap();
+            let cost = cs.num_constraints() - before;
+            assert_eq!(bits.len(), size);
+            let packed = Boolean::le_bits_to_fp(&bits).unwrap();
+            assert_eq!(packed.value().unwrap(), value);
+            assert_eq!(rest.value().unwrap(), Fr::zero());
+            assert!(cs.is_satisfied().unwrap());
+            let expected = if size < full_size {
+                size + 1
+            } else {
+                let before = cs.num_constraints();
+                let _ = var.to_bits_le().unwrap();
+                cs.num_constraints() - before
+            };
+            assert_eq!(cost, expected);
+            // Near-full decompositions do not need a separate remainder.
+            assert_eq!(rest.is_constant(), size >= full_size - 1);
+        }
+    }
+
+    #[test]
+    fn test_weighted_bit_sum() {
+        let mut rng = ark_std::test_rng();
+        let base = Fr::rand(&mut rng);
+        let bits = [true, false, true, true, false, true];
+        let packed = Fr::from(0b101101u64);
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let bit_vars = bits
+            .iter()
+            .map(|b| Boolean::new_witness(cs.clone(), || Ok(*b)).unwrap())
+            .collect::<Vec<_>>();
+        let base_var = FpVar::new_witness(cs.clone(), || Ok(base)).unwrap();
+        let before = cs.num_constraints();
+        let sum = FpVar::weighted_bit_sum(&bit_vars, &base_var).unwrap();
+        assert_eq!(cs.num_constraints() - before, 1);
+        assert_eq!(sum.value().unwrap(), base * packed);
+        assert!(cs.is_satisfied().unwrap());
+
+        // A constant base, or constant bits, do not require a constraint.
+        let before = cs.num_constraints();
+        let constant_bits = bits.map(Boolean::Constant);
+        let sum = FpVar::weighted_bit_sum(&bit_vars, &FpVar::Constant(base)).unwrap();
+        assert_eq!(sum.value().unwrap(), base * packed);
+        let sum = FpVar::weighted_bit_sum(&constant_bits, &base_var).unwrap();
+        assert_eq!(sum.value().unwrap(), base * packed);
+        let sum = FpVar::weighted_bit_sum(&constant_bits, &FpVar::Constant(base)).unwrap();
+        assert!(sum.is_constant());
+        assert_eq!(cs.num_constraints(), before);
+    }
+
+    #[test]
+    fn test_select_between_constants() {
+        let mut rng = ark_std::test_rng();
+        let (t, f) = (Fr::rand(&mut rng), Fr::rand(&mut rng));
+        for choice in [true, false] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let cond = Boolean::new_witness(cs.clone(), || Ok(choice)).unwrap();
+            let (lcs, witnesses) = (num_lcs(&cs), cs.num_witness_variables());
+            let constraints = cs.num_constraints();
+            let selected = cond
+                .select(&FpVar::Constant(t), &FpVar::Constant(f))
+                .unwrap();
+            assert_eq!(num_lcs(&cs), lcs + 1);
+            assert_eq!(cs.num_witness_variables(), witnesses);
+            assert_eq!(cs.num_constraints(), constraints);
+            assert_eq!(selected.value().unwrap(), if choice { t } else { f });
+
+            // The selected value is bound to `cond`.
+            let expected = if choice { t } else { f };
+            selected.enforce_equal(&FpVar::Constant(expected)).unwrap();
+            assert!(cs.is_satisfied().unwrap());
+            selected.enforce_equal(&FpVar::Constant(t + f)).unwrap();
+            assert!(!cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_new_constant_cached() {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let c = Fr::from(42u64);
+        let lcs = num_lcs(&cs);
+        let first = AllocatedFp::new_constant_cached(cs.clone(), c).unwrap();
+        for _ in 0..99 {
+            let again = AllocatedFp::new_constant_cached(cs.clone(), c).unwrap();
+            assert_eq!(again.variable, first.variable);
+            assert_eq!(again.value().unwrap(), c);
+        }
+        assert_eq!(num_lcs(&cs), lcs + 1);
+
+        // Other values, and other constraint systems, get their own variables.
+        let other = AllocatedFp::new_constant_cached(cs.clone(), c + Fr::one()).unwrap();
+        assert_ne!(other.variable, first.variable);
+        assert_eq!(num_lcs(&cs), lcs + 2);
+        let other_cs = ConstraintSystem::<Fr>::new_ref();
+        let other_lcs = num_lcs(&other_cs);
+        let _ = AllocatedFp::new_constant_cached(other_cs.clone(), c).unwrap();
+        assert_eq!(num_lcs(&other_cs), other_lcs + 1);
+        assert_eq!(num_lcs(&cs), lcs + 2);
+
+        let witness = FpVar::new_witness(cs.clone(), || Ok(c)).unwrap();
+        witness.enforce_equal(&FpVar::Var(first)).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_from_linear_combination() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let (a, b) = (Fr::rand(&mut rng), Fr::rand(&mut rng));
+        let x = FpVar::new_witness(cs.clone(), || Ok(a)).unwrap();
+        let y = FpVar::new_witness(cs.clone(), || Ok(b)).unwrap();
+        let (two, three, five) = (Fr::from(2u64), Fr::from(3u64), Fr::from(5u64));
+        let terms = [
+            (two, x.clone()),
+            (three, y.clone()),
+            (three, x.clone()),
+            (Fr::one(), FpVar::Constant(five)),
+            (-three, y.clone()),
+            (two, FpVar::Constant(Fr::one())),
+        ];
+        let lcs = num_lcs(&cs);
+        let constraints = cs.num_constraints();
+        let sum = FpVar::from_linear_combination(cs.clone(), &terms).unwrap();
+        assert_eq!(num_lcs(&cs), lcs + 1);
+        assert_eq!(cs.num_constraints(), constraints);
+        assert_eq!(sum.value().unwrap(), five * a + Fr::from(7u64));
+
+        let expected = FpVar::new_input(cs.clone(), || Ok(five * a + Fr::from(7u64))).unwrap();
+        sum.enforce_equal(&expected).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+
+        // Constant terms alone fold into a constant.
+        let terms = [(two, FpVar::Constant(five)), (three, FpVar::Constant(two))];
+        let sum = FpVar::from_linear_combination(cs.clone(), &terms).unwrap();
+        assert!(sum.is_constant());
+        assert_eq!(sum.value().unwrap(), Fr::from(16u64));
+    }
+
+    #[test]
+    fn test_sum_with_coefficients() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let terms = (0..8)
+            .map(|i| {
+                let value = Fr::rand(&mut rng);
+                let var = if i % 3 == 0 {
+                    FpVar::Constant(value)
+                } else {
+                    FpVar::new_witness(cs.clone(), || Ok(value)).unwrap()
+                };
+                (Fr::rand(&mut rng), var)
+            })
+            .collect::<Vec<_>>();
+
+        let (lcs, constraints) = (num_lcs(&cs), cs.num_constraints());
+        let sum = FpVar::sum_with_coefficients(terms.iter().cloned());
+        assert_eq!(num_lcs(&cs), lcs + 1);
+        assert_eq!(cs.num_constraints(), constraints);
+
+        let naive = terms.iter().fold(FpVar::zero(), |acc, (c, v)| acc + v * *c);
+        assert!(num_lcs(&cs) > lcs + 2);
+        assert_eq!(sum.value().unwrap(), naive.value().unwrap());
+        sum.enforce_equal(&naive).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+
+        let empty = FpVar::<Fr>::sum_with_coefficients(Vec::new());
+        assert!(empty.is_constant());
+        assert!(empty.value().unwrap().is_zero());
+    }
+
+    #[test]
+    fn test_try_value() {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let (five, seven) = (Fr::from(5u64), Fr::from(7u64));
+        let assigned = FpVar::new_witness(cs.clone(), || Ok(five)).unwrap();
+        let variable = cs.new_witness_variable(|| Ok(five)).unwrap();
+        let unassigned = FpVar::Var(AllocatedFp::new(None, variable, cs.clone()));
+
+        let constraints = cs.num_constraints();
+        assert_eq!(assigned.try_value(), Some(five));
+        assert_eq!(assigned.value_or(seven), five);
+        assert_eq!(unassigned.try_value(), None);
+        assert_eq!(unassigned.value_or(seven), seven);
+        assert!(unassigned.value().is_err());
+        assert_eq!(FpVar::Constant(seven).try_value(), Some(seven));
+        assert_eq!(cs.num_constraints(), constraints);
+
+        // The value is still missing after an operation on `unassigned`.
+        let sum = &assigned + &unassigned;
+        assert_eq!(sum.try_value(), None);
+        assert_eq!(sum.value_or(Fr::zero()), Fr::zero());
+    }
+
+    #[test]
+    fn test_scale_and_shift() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let x = Fr::rand(&mut rng);
+        let x_var = FpVar::new_witness(cs.clone(), || Ok(x)).unwrap();
+        let (scale, shift) = (Fr::rand(&mut rng), Fr::rand(&mut rng));
+
+        let (lcs, constraints) = (num_lcs(&cs), cs.num_constraints());
+        let result = x_var.scale_and_shift(scale, shift).unwrap();
+        assert_eq!(num_lcs(&cs), lcs + 1);
+        assert_eq!(cs.num_constraints(), constraints);
+        assert_eq!(result.value().unwrap(), scale * x + shift);
+
+        let zero = x_var.scale_and_shift(Fr::zero(), shift).unwrap();
+        assert!(zero.is_constant());
+        assert_eq!(zero.value().unwrap(), shift);
+        let one = x_var.scale_and_shift(Fr::one(), shift).unwrap();
+        assert_eq!(one.value().unwrap(), x + shift);
+        let constant = FpVar::Constant(x).scale_and_shift(scale, shift).unwrap();
+        assert_eq!(constant.value().unwrap(), scale * x + shift);
+        assert_eq!(num_lcs(&cs), lcs + 2);
+
+        let expected = FpVar::new_input(cs.clone(), || Ok(scale * x + shift)).unwrap();
+        result.enforce_equal(&expected).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_mul_equals_constant() {
+        let mut rng = ark_std::test_rng();
+        for offset in [Fr::zero(), Fr::one()] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let (a, b) = (Fr::rand(&mut rng), Fr::rand(&mut rng));
+            let a_var = FpVar::new_witness(cs.clone(), || Ok(a)).unwrap();
+            let b_var = FpVar::new_witness(cs.clone(), || Ok(b)).unwrap();
+            let c = a * b + offset;
+
+            let (witnesses, lcs) = (cs.num_witness_variables(), num_lcs(&cs));
+            let constraints = cs.num_constraints();
+            a_var.mul_equals_constant(&b_var, c).unwrap();
+            a_var.mul_equals(&b_var, &FpVar::Constant(c)).unwrap();
+            assert_eq!(cs.num_witness_variables(), witnesses);
+            assert_eq!(num_lcs(&cs), lcs);
+            assert_eq!(cs.num_constraints(), constraints + 2);
+
+            FpVar::Constant(a).mul_equals_constant(&b_var, c).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), offset.is_zero());
+        }
+    }
+
+    #[test]
+    fn test_enforce_not_zero() {
+        let mut rng = ark_std::test_rng();
+        for (value, expected) in [(Fr::rand(&mut rng), true), (Fr::zero(), false)] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let var = FpVar::new_witness(cs.clone(), || Ok(value)).unwrap();
+            var.enforce_not_zero().unwrap();
+            assert_eq!(cs.num_constraints(), 1);
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+
+        assert!(FpVar::Constant(Fr::one()).enforce_not_zero().is_ok());
+        assert!(matches!(
+            FpVar::Constant(Fr::zero()).enforce_not_zero(),
+            Err(SynthesisError::Unsatisfiable)
+        ));
+    }
+
+    #[test]
+    fn test_three_bit_cond_neg_lookup_auto() {
+        let mut rng = ark_std::test_rng();
+        let table = (0..4).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        for index in 0..8u8 {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let bits = (0..3)
+                .map(|i| Boolean::new_witness(cs.clone(), || Ok((index >> i) & 1 == 1)).unwrap())
+                .collect::<Vec<_>>();
+            let auto = FpVar::three_bit_cond_neg_lookup_auto(&bits, &table).unwrap();
+            let b0b1 = &bits[0] & &bits[1];
+            let manual = FpVar::three_bit_cond_neg_lookup(&bits, &b0b1, &table).unwrap();
+            assert_eq!(auto.value().unwrap(), manual.value().unwrap());
+            assert!(cs.is_satisfied().unwrap());
+
+            // A wrong product breaks the lookup constraint.
+            let wrong = Boolean::new_witness(cs.clone(), || Ok(index & 3 != 3)).unwrap();
+            FpVar::three_bit_cond_neg_lookup(&bits, &wrong, &table).unwrap();
+            assert!(!cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_as_fresh_witness() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let mut sum = FpVar::new_witness(cs.clone(), || Ok(Fr::rand(&mut rng))).unwrap();
+        for _ in 0..16 {
+            let x = FpVar::new_witness(cs.clone(), || Ok(Fr::rand(&mut rng))).unwrap();
+            sum = (sum + x) * Fr::from(3u64);
+        }
+
+        let (witnesses, lcs) = (cs.num_witness_variables(), num_lcs(&cs));
+        let constraints = cs.num_constraints();
+        let fresh = sum.as_fresh_witness().unwrap();
+        assert_eq!(fresh.value().unwrap(), sum.value().unwrap());
+        assert_eq!(cs.num_witness_variables(), witnesses + 1);
+        assert_eq!(num_lcs(&cs), lcs);
+        assert_eq!(cs.num_constraints(), constraints + 1);
+        assert!(cs.is_satisfied().unwrap());
+
+        let constant = FpVar::Constant(Fr::from(5u64)).as_fresh_witness().unwrap();
+        assert!(constant.is_constant());
+        assert_eq!(cs.num_constraints(), constraints + 1);
+    }
 }
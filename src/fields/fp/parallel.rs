@@ -0,0 +1,79 @@
+use crate::{fields::fp::FpVar, prelude::*, Vec};
+use ark_ff::PrimeField;
+use ark_relations::gr1cs::{Namespace, SynthesisError};
+use rayon::prelude::*;
+
+impl<F: PrimeField> FpVar<F> {
+    /// Allocates a witness for each closure in `fs`, evaluating the closures
+    /// in parallel.
+    ///
+    /// The closures must be independent of each other. Only their evaluation
+    /// is parallelized: the witnesses are then registered in the constraint
+    /// system serially and in order, so the resulting constraint system is
+    /// identical to the one obtained by calling [`AllocVar::new_witness`] on
+    /// each closure in turn. If the constraint system is in setup mode, the
+    /// closures are not evaluated.
+    #[tracing::instrument(target = "gr1cs", skip(cs, fs))]
+    pub fn new_witness_batch_parallel<Func>(
+        cs: impl Into<Namespace<F>>,
+        fs: Vec<Func>,
+    ) -> Result<Vec<Self>, SynthesisError>
+    where
+        Func: FnOnce() -> Result<F, SynthesisError> + Send,
+    {
+        let ns = cs.into();
+        let cs = ns.cs();
+        if cs.is_in_setup_mode() {
+            return fs
+                .into_iter()
+                .map(|f| Self::new_witness(cs.clone(), f))
+                .collect();
+        }
+        let values = fs.into_par_iter().map(|f| f()).collect::<Vec<_>>();
+        values
+            .into_iter()
+            .map(|value| Self::new_witness(cs.clone(), || value))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{alloc::AllocVar, fields::fp::FpVar, GR1CSVar};
+    use ark_ff::Field;
+    use ark_relations::gr1cs::{ConstraintSystem, SynthesisError};
+    use ark_test_curves::bls12_381::Fr;
+
+    #[test]
+    fn test_new_witness_batch_parallel() {
+        let values = (0..1000u64)
+            .map(|i| Fr::from(i + 1).pow([1000]))
+            .collect::<Vec<_>>();
+        let closures = values
+            .iter()
+            .map(|v| move || Ok::<_, SynthesisError>(*v))
+            .collect::<Vec<_>>();
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let parallel = FpVar::new_witness_batch_parallel(cs.clone(), closures).unwrap();
+        let serial_cs = ConstraintSystem::<Fr>::new_ref();
+        let serial = values
+            .iter()
+            .map(|v| FpVar::new_witness(serial_cs.clone(), || Ok(*v)).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            cs.num_witness_variables(),
+            serial_cs.num_witness_variables()
+        );
+        // The witnesses are registered in order, with the same values.
+        for (a, b) in parallel.iter().zip(&serial) {
+            assert_eq!(a.value().unwrap(), b.value().unwrap());
+            match (a, b) {
+                (FpVar::Var(a), FpVar::Var(b)) => assert_eq!(a.variable, b.variable),
+                _ => panic!("expected variables"),
+            }
+        }
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{fields::fp::FpVar, prelude::*, Vec};
+use ark_ff::PrimeField;
+use ark_relations::gr1cs::SynthesisError;
+
+impl<F: PrimeField> FpVar<F> {
+    /// Enforces that every row of `trace` (except the first) is obtained by
+    /// applying `transition` to the preceding row.
+    ///
+    /// `transition` is given the current row and the next row, and outputs the
+    /// expected next row; the latter is passed so that transitions can use it
+    /// as a hint (e.g. to avoid allocating intermediate witnesses). Each row of
+    /// `trace` is then enforced to be equal to the output of `transition`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the output of `transition` does not have the same length as
+    /// the next row.
+    #[tracing::instrument(target = "gr1cs", skip(trace, transition))]
+    pub fn enforce_trace(
+        trace: &[Vec<Self>],
+        transition: impl Fn(&[Self], &[Self]) -> Result<Vec<Self>, SynthesisError>,
+    ) -> Result<(), SynthesisError> {
+        for rows in trace.windows(2) {
+            let (cur, next) = (&rows[0], &rows[1]);
+            let expected = transition(cur, next)?;
+            next.as_slice().enforce_equal(&expected)?;
+        }
+        Ok(())
+    }
+
+    /// Enforces that, for each `(row, column, value)` in `boundaries`, the cell
+    /// `trace[row][column]` equals the constant `value`.
+    ///
+    /// This is used to pin down the initial and final conditions of a trace
+    /// whose transitions are enforced via [`Self::enforce_trace`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if any `(row, column)` is out of bounds for `trace`.
+    #[tracing::instrument(target = "gr1cs", skip(trace, boundaries))]
+    pub fn enforce_boundary(
+        trace: &[Vec<Self>],
+        boundaries: &[(usize, usize, F)],
+    ) -> Result<(), SynthesisError> {
+        for (row, column, value) in boundaries {
+            trace[*row][*column].enforce_equal(&Self::constant(*value))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{alloc::AllocVar, fields::fp::FpVar};
+    use ark_relations::gr1cs::{ConstraintSystem, ConstraintSystemRef, SynthesisError};
+    use ark_std::vec::Vec;
+    use ark_test_curves::bls12_381::Fr;
+
+    /// Maps `(a, b)` to `(b, a + b)`.
+    fn fibonacci(cur: &[FpVar<Fr>], _: &[FpVar<Fr>]) -> Result<Vec<FpVar<Fr>>, SynthesisError> {
+        Ok(vec![cur[1].clone(), &cur[0] + &cur[1]])
+    }
+
+    fn fibonacci_trace(len: usize) -> Vec<[u64; 2]> {
+        let mut row = [0u64, 1];
+        (0..len)
+            .map(|_| {
+                let cur = row;
+                row = [row[1], row[0] + row[1]];
+                cur
+            })
+            .collect()
+    }
+
+    fn alloc_trace(cs: ConstraintSystemRef<Fr>, trace: &[[u64; 2]]) -> Vec<Vec<FpVar<Fr>>> {
+        trace
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|v| FpVar::new_witness(cs.clone(), || Ok(Fr::from(*v))).unwrap())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_enforce_trace() {
+        let valid = fibonacci_trace(10);
+        let mut invalid = valid.clone();
+        invalid[6][1] += 1;
+        for (trace, expected) in [(valid, true), (invalid, false)] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let trace = alloc_trace(cs.clone(), &trace);
+            FpVar::enforce_trace(&trace, fibonacci).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_enforce_boundary() {
+        // The last row of a 10-row trace is `(F(9), F(10))`.
+        let trace = fibonacci_trace(10);
+        // A trace with valid transitions but the wrong initial conditions.
+        let shifted = fibonacci_trace(11)[1..].to_vec();
+        for (trace, output, expected) in [
+            (&trace, 55u64, true),
+            (&trace, 54, false),
+            (&shifted, 89, false),
+        ] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let last = trace.len() - 1;
+            let trace = alloc_trace(cs.clone(), trace);
+            FpVar::enforce_trace(&trace, fibonacci).unwrap();
+            assert!(cs.is_satisfied().unwrap());
+
+            let boundaries = [(0, 0, 0u64), (0, 1, 1), (last, 1, output)]
+                .map(|(row, column, value)| (row, column, Fr::from(value)));
+            FpVar::enforce_boundary(&trace, &boundaries).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+}
@@ -24,6 +24,11 @@ pub mod quadratic_extension;
 /// That is, it implements the R1CS equivalent of `ark_ff::Fp*`.
 pub mod fp;
 
+/// This module contains addition chains, which precompute the squarings and
+/// multiplications needed to raise field variables to a fixed power.
+pub mod addition_chain;
+pub use addition_chain::AdditionChain;
+
 /// This module contains a generic implementation of "emulated" prime field
 /// variables. It emulates `Fp` arithmetic using `Fq` operations, where `p !=
 /// q`.
@@ -244,4 +249,15 @@ pub trait FieldVar<F: Field, ConstraintF: PrimeField>:
         }
         Ok(res)
     }
+
+    /// Sets `self = self^exp`, where `exp` is interpreted as a little-endian
+    /// u64-decomposition of an integer.
+    ///
+    /// To raise many variables to the same power, compute the
+    /// [`AdditionChain`] for `exp` once and reuse it via
+    /// [`AdditionChain::pow`].
+    fn pow_by_constant_in_place(&mut self, exp: &[u64]) -> Result<&mut Self, SynthesisError> {
+        *self = AdditionChain::new(exp).pow(self)?;
+        Ok(self)
+    }
 }
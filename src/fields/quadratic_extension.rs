@@ -76,6 +76,16 @@ where
         *self = (&*self).mul_by_base_field_constant(fe);
     }
 
+    /// Multiplies `self` by a variable of the base field.
+    ///
+    /// This multiplies each coefficient by `fe`, which is cheaper than
+    /// embedding `fe` into the extension field and performing a full
+    /// multiplication.
+    #[inline]
+    pub fn mul_by_base_field_var(&self, fe: &BF) -> Result<Self, SynthesisError> {
+        Ok(Self::new(&self.c0 * fe, &self.c1 * fe))
+    }
+
     /// This is only to be used when the element is *known* to be in the
     /// cyclotomic subgroup.
     #[inline]
@@ -265,6 +275,40 @@ where
         Ok(())
     }
 
+    /// Computes the inner product of `this` and `other`.
+    ///
+    /// This applies Karatsuba multiplication to the whole sum rather than to
+    /// each product: the three component sums are computed once via
+    /// `BF::inner_product`, which for `FpVar` creates a single linear
+    /// combination per sum.
+    #[tracing::instrument(target = "gr1cs")]
+    fn inner_product(this: &[Self], other: &[Self]) -> Result<Self, SynthesisError> {
+        // Karatsuba multiplication, summed across all pairs:
+        //     v0 = sum_i A_i.c0 * B_i.c0
+        //     v1 = sum_i A_i.c1 * B_i.c1
+        //     v2 = sum_i (A_i.c0 + A_i.c1) * (B_i.c0 + B_i.c1)
+        //     result.c0 = v0 + non_residue * v1
+        //     result.c1 = v2 - v0 - v1
+        if this.len() != other.len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let components = |v: &[Self]| {
+            let c0 = v.iter().map(|x| x.c0.clone()).collect::<Vec<_>>();
+            let c1 = v.iter().map(|x| x.c1.clone()).collect::<Vec<_>>();
+            let sums = v.iter().map(|x| &x.c0 + &x.c1).collect::<Vec<_>>();
+            (c0, c1, sums)
+        };
+        let (a0, a1, a_sums) = components(this);
+        let (b0, b1, b_sums) = components(other);
+        let v0 = BF::inner_product(&a0, &b0)?;
+        let v1 = BF::inner_product(&a1, &b1)?;
+        let v2 = BF::inner_product(&a_sums, &b_sums)?;
+
+        let c0 = &v0 + &Self::mul_base_field_by_nonresidue(&v1)?;
+        let c1 = &(&v2 - &v0) - &v1;
+        Ok(Self::new(c0, c1))
+    }
+
     #[tracing::instrument(target = "gr1cs")]
     fn frobenius_map(&self, power: usize) -> Result<Self, SynthesisError> {
         let mut result = self.clone();
@@ -592,3 +636,70 @@ where
         Self::new(c0, c1)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        alloc::AllocVar,
+        fields::{fp::FpVar, fp2::Fp2Var, FieldVar},
+        test_utils::num_lcs,
+        GR1CSVar,
+    };
+    use ark_bls12_381::{Fq, Fq2, Fq2Config};
+    use ark_ff::Zero;
+    use ark_relations::gr1cs::ConstraintSystem;
+    use ark_std::UniformRand;
+
+    #[test]
+    fn test_mul_by_base_field_var() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let a = Fq2::rand(&mut rng);
+        let s = Fq::rand(&mut rng);
+        let a_var = Fp2Var::<Fq2Config>::new_witness(cs.clone(), || Ok(a)).unwrap();
+        let s_var = FpVar::new_witness(cs.clone(), || Ok(s)).unwrap();
+
+        let num_constraints = cs.num_constraints();
+        let result = a_var.mul_by_base_field_var(&s_var).unwrap();
+        assert_eq!(cs.num_constraints() - num_constraints, 2);
+
+        let embedded = Fp2Var::new(s_var, FpVar::zero());
+        let expected = &a_var * &embedded;
+        assert_eq!(result.value().unwrap(), expected.value().unwrap());
+        assert_eq!(result.value().unwrap(), a * Fq2::new(s, Fq::zero()));
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_inner_product() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let mut alloc = || {
+            (0..5)
+                .map(|_| Fp2Var::<Fq2Config>::new_witness(cs.clone(), || Ok(Fq2::rand(&mut rng))))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
+        };
+        let (a, b) = (alloc(), alloc());
+        // Constant entries are folded into the linear combinations.
+        let b = [&b[..4], &[Fp2Var::constant(Fq2::rand(&mut rng))]].concat();
+
+        let (constraints, lcs) = (cs.num_constraints(), num_lcs(&cs));
+        let naive: Fp2Var<Fq2Config> = a.iter().zip(&b).map(|(x, y)| x * y).sum();
+        let naive_constraints = cs.num_constraints() - constraints;
+        let naive_lcs = num_lcs(&cs) - lcs;
+
+        let (constraints, lcs) = (cs.num_constraints(), num_lcs(&cs));
+        let result = Fp2Var::inner_product(&a, &b).unwrap();
+        let optimized_constraints = cs.num_constraints() - constraints;
+        let optimized_lcs = num_lcs(&cs) - lcs;
+
+        assert_eq!(result.value().unwrap(), naive.value().unwrap());
+        assert_eq!(optimized_constraints, 3 * 4);
+        assert!(optimized_constraints <= naive_constraints);
+        assert!(optimized_lcs < naive_lcs);
+        assert!(cs.is_satisfied().unwrap());
+
+        assert!(Fp2Var::inner_product(&a, &b[..4]).is_err());
+    }
+}
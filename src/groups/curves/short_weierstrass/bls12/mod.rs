@@ -51,6 +51,19 @@ impl<P: Bls12Config> G1PreparedVar<P> {
         let g = q.to_affine()?;
         Ok(Self(g))
     }
+
+    /// Constructs `Self` from a constant point in G1.
+    ///
+    /// Unlike [`Self::from_group_var`], this does not need to convert a
+    /// projective point into affine form in-circuit, and so creates no
+    /// variables or constraints.
+    pub fn from_constant(value: GroupAffine<P::G1Config>) -> Self {
+        Self(AffineVar::new(
+            FpVar::Constant(value.x),
+            FpVar::Constant(value.y),
+            Boolean::Constant(value.infinity),
+        ))
+    }
 }
 
 impl<P: Bls12Config> AllocVar<G1Prepared<P>, P::Fp> for G1PreparedVar<P> {
@@ -98,6 +111,45 @@ impl<P: Bls12Config> ToBytesGadget<P::Fp> for G1PreparedVar<P> {
     }
 }
 
+impl<P: Bls12Config> CondSelectGadget<P::Fp> for G1PreparedVar<P> {
+    #[inline]
+    #[tracing::instrument(target = "gr1cs")]
+    fn conditionally_select(
+        cond: &Boolean<P::Fp>,
+        true_value: &Self,
+        false_value: &Self,
+    ) -> Result<Self, SynthesisError> {
+        cond.select(&true_value.0, &false_value.0).map(Self)
+    }
+}
+
+impl<P: Bls12Config> EqGadget<P::Fp> for G1PreparedVar<P> {
+    #[tracing::instrument(target = "gr1cs")]
+    fn is_eq(&self, other: &Self) -> Result<Boolean<P::Fp>, SynthesisError> {
+        let x_equal = self.0.x.is_eq(&other.0.x)?;
+        let y_equal = self.0.y.is_eq(&other.0.y)?;
+        let infinity_equal = self.0.infinity.is_eq(&other.0.infinity)?;
+        Boolean::kary_and(&[x_equal, y_equal, infinity_equal])
+    }
+
+    #[tracing::instrument(target = "gr1cs")]
+    fn conditional_enforce_equal(
+        &self,
+        other: &Self,
+        should_enforce: &Boolean<P::Fp>,
+    ) -> Result<(), SynthesisError> {
+        self.0
+            .x
+            .conditional_enforce_equal(&other.0.x, should_enforce)?;
+        self.0
+            .y
+            .conditional_enforce_equal(&other.0.y, should_enforce)?;
+        self.0
+            .infinity
+            .conditional_enforce_equal(&other.0.infinity, should_enforce)
+    }
+}
+
 type Fp2G<P> = Fp2Var<<P as Bls12Config>::Fp2Config>;
 type LCoeff<P> = (Fp2G<P>, Fp2G<P>);
 /// Represents the cached precomputation that can be performed on a G2 element
@@ -190,6 +242,67 @@ impl<P: Bls12Config> ToBytesGadget<P::Fp> for G2PreparedVar<P> {
     }
 }
 
+/// Selecting between prepared elements of G2 requires their `ell_coeffs` to
+/// have the same length; otherwise, this returns
+/// `SynthesisError::Unsatisfiable`.
+impl<P: Bls12Config> CondSelectGadget<P::Fp> for G2PreparedVar<P> {
+    #[inline]
+    #[tracing::instrument(target = "gr1cs")]
+    fn conditionally_select(
+        cond: &Boolean<P::Fp>,
+        true_value: &Self,
+        false_value: &Self,
+    ) -> Result<Self, SynthesisError> {
+        if true_value.ell_coeffs.len() != false_value.ell_coeffs.len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let ell_coeffs = true_value
+            .ell_coeffs
+            .iter()
+            .zip(&false_value.ell_coeffs)
+            .map(|((t0, t1), (f0, f1))| Ok((cond.select(t0, f0)?, cond.select(t1, f1)?)))
+            .collect::<Result<_, SynthesisError>>()?;
+        Ok(Self { ell_coeffs })
+    }
+}
+
+/// Comparing prepared elements of G2 requires their `ell_coeffs` to have the
+/// same length; otherwise, this returns `SynthesisError::Unsatisfiable`.
+impl<P: Bls12Config> EqGadget<P::Fp> for G2PreparedVar<P> {
+    #[tracing::instrument(target = "gr1cs")]
+    fn is_eq(&self, other: &Self) -> Result<Boolean<P::Fp>, SynthesisError> {
+        if self.ell_coeffs.len() != other.ell_coeffs.len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let mut results = Vec::with_capacity(2 * self.ell_coeffs.len());
+        for ((a0, a1), (b0, b1)) in self.ell_coeffs.iter().zip(&other.ell_coeffs) {
+            results.push(a0.is_eq(b0)?);
+            results.push(a1.is_eq(b1)?);
+        }
+        if results.is_empty() {
+            Ok(Boolean::TRUE)
+        } else {
+            Boolean::kary_and(&results)
+        }
+    }
+
+    #[tracing::instrument(target = "gr1cs")]
+    fn conditional_enforce_equal(
+        &self,
+        other: &Self,
+        should_enforce: &Boolean<P::Fp>,
+    ) -> Result<(), SynthesisError> {
+        if self.ell_coeffs.len() != other.ell_coeffs.len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        for ((a0, a1), (b0, b1)) in self.ell_coeffs.iter().zip(&other.ell_coeffs) {
+            a0.conditional_enforce_equal(b0, should_enforce)?;
+            a1.conditional_enforce_equal(b1, should_enforce)?;
+        }
+        Ok(())
+    }
+}
+
 impl<P: Bls12Config> G2PreparedVar<P> {
     /// Constructs `Self` from a `G2Var`.
     #[tracing::instrument(target = "gr1cs")]
@@ -212,6 +325,36 @@ impl<P: Bls12Config> G2PreparedVar<P> {
         Ok(Self { ell_coeffs })
     }
 
+    /// Outputs `2 * r`, together with the coefficients of the line tangent to
+    /// `r`, as used in the doubling step of the Miller loop.
+    ///
+    /// `two_inv` must be the inverse of two in `P::Fp`. The constraint system
+    /// will not be satisfied if the `y`-coordinate of `r` is zero.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn line_double(
+        r: &G2AffineVar<P>,
+        two_inv: &P::Fp,
+    ) -> Result<(G2AffineVar<P>, LCoeff<P>), SynthesisError> {
+        let mut r = r.clone();
+        let coeffs = Self::double(&mut r, two_inv)?;
+        Ok((r, coeffs))
+    }
+
+    /// Outputs `r + q`, together with the coefficients of the line through `r`
+    /// and `q`, as used in the addition step of the Miller loop.
+    ///
+    /// The constraint system will not be satisfied if `r` and `q` have the
+    /// same `x`-coordinate.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn line_add(
+        r: &G2AffineVar<P>,
+        q: &G2AffineVar<P>,
+    ) -> Result<(G2AffineVar<P>, LCoeff<P>), SynthesisError> {
+        let mut r = r.clone();
+        let coeffs = Self::add(&mut r, q)?;
+        Ok((r, coeffs))
+    }
+
     #[tracing::instrument(target = "gr1cs")]
     fn double(r: &mut G2AffineVar<P>, two_inv: &P::Fp) -> Result<LCoeff<P>, SynthesisError> {
         let a = r.y.inverse()?;
@@ -257,3 +400,121 @@ impl<P: Bls12Config> G2PreparedVar<P> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{alloc::AllocVar, boolean::Boolean, eq::EqGadget, GR1CSVar};
+    use ark_bls12_381::{Config, Fq, G1Projective, G2Projective};
+    use ark_ec::CurveGroup;
+    use ark_relations::gr1cs::ConstraintSystem;
+    use ark_std::{UniformRand, Zero};
+
+    #[test]
+    fn test_line_coefficients_match_prepared() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::new_ref();
+        let q =
+            G2Var::<Config>::new_witness(cs.clone(), || Ok(G2Projective::rand(&mut rng))).unwrap();
+        let prepared = G2PreparedVar::<Config>::from_group_var(&q).unwrap();
+
+        let q = q.to_affine().unwrap();
+        let two_inv = Fq::from(2u64).inverse().unwrap();
+        let mut ell_coeffs = vec![];
+        let mut r = q.clone();
+        for i in BitIteratorBE::new(Config::X).skip(1) {
+            let (doubled, coeffs) = G2PreparedVar::<Config>::line_double(&r, &two_inv).unwrap();
+            ell_coeffs.push(coeffs);
+            r = doubled;
+            if i {
+                let (sum, coeffs) = G2PreparedVar::<Config>::line_add(&r, &q).unwrap();
+                ell_coeffs.push(coeffs);
+                r = sum;
+            }
+        }
+
+        assert_eq!(ell_coeffs.len(), prepared.ell_coeffs.len());
+        for ((a0, a1), (b0, b1)) in ell_coeffs.iter().zip(&prepared.ell_coeffs) {
+            assert_eq!(a0.value().unwrap(), b0.value().unwrap());
+            assert_eq!(a1.value().unwrap(), b1.value().unwrap());
+        }
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_g1_prepared_from_constant() {
+        let mut rng = ark_std::test_rng();
+        for point in [G1Projective::rand(&mut rng), G1Projective::zero()] {
+            let cs = ConstraintSystem::new_ref();
+            let constant = G1PreparedVar::<Config>::from_constant(point.into_affine());
+            assert_eq!(cs.num_constraints(), 0);
+            assert!(constant.0.is_constant());
+
+            let witness = G1Var::<Config>::new_witness(cs.clone(), || Ok(point)).unwrap();
+            let prepared = G1PreparedVar::<Config>::from_group_var(&witness).unwrap();
+            assert!(cs.num_constraints() > 0);
+            assert_eq!(constant.value().unwrap(), prepared.value().unwrap());
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_g1_prepared_select_and_eq() {
+        let mut rng = ark_std::test_rng();
+        let (a, b) = (G1Projective::rand(&mut rng), G1Projective::rand(&mut rng));
+        for choice in [true, false] {
+            let cs = ConstraintSystem::new_ref();
+            let prepare = |point: G1Projective| {
+                let var = G1Var::<Config>::new_witness(cs.clone(), || Ok(point)).unwrap();
+                G1PreparedVar::<Config>::from_group_var(&var).unwrap()
+            };
+            let (a_var, b_var) = (prepare(a), prepare(b));
+            let cond = Boolean::new_witness(cs.clone(), || Ok(choice)).unwrap();
+            let selected = cond.select(&a_var, &b_var).unwrap();
+            let expected = if choice { a } else { b };
+            let expected_var = if choice { &a_var } else { &b_var };
+            let other_var = if choice { &b_var } else { &a_var };
+            assert_eq!(selected.value().unwrap(), expected.into_affine().into());
+            assert!(selected.is_eq(expected_var).unwrap().value().unwrap());
+            assert!(!selected.is_eq(other_var).unwrap().value().unwrap());
+            selected.enforce_equal(expected_var).unwrap();
+            assert!(cs.is_satisfied().unwrap());
+            selected.enforce_equal(other_var).unwrap();
+            assert!(!cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_g2_prepared_select_and_eq() {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::new_ref();
+        let prepare = |point: G2Projective| {
+            let var = G2Var::<Config>::new_witness(cs.clone(), || Ok(point)).unwrap();
+            G2PreparedVar::<Config>::from_group_var(&var).unwrap()
+        };
+        let a = prepare(G2Projective::rand(&mut rng));
+        let b = prepare(G2Projective::rand(&mut rng));
+        let cond = Boolean::new_witness(cs.clone(), || Ok(false)).unwrap();
+        let selected = cond.select(&a, &b).unwrap();
+        for ((s0, s1), (b0, b1)) in selected.ell_coeffs.iter().zip(&b.ell_coeffs) {
+            assert_eq!(s0.value().unwrap(), b0.value().unwrap());
+            assert_eq!(s1.value().unwrap(), b1.value().unwrap());
+        }
+        assert!(selected.is_eq(&b).unwrap().value().unwrap());
+        assert!(!selected.is_eq(&a).unwrap().value().unwrap());
+        selected.enforce_equal(&b).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+
+        let truncated = G2PreparedVar::<Config> {
+            ell_coeffs: a.ell_coeffs[1..].to_vec(),
+        };
+        assert!(matches!(
+            cond.select(&truncated, &b),
+            Err(SynthesisError::Unsatisfiable)
+        ));
+        assert!(matches!(
+            truncated.is_eq(&b),
+            Err(SynthesisError::Unsatisfiable)
+        ));
+    }
+}
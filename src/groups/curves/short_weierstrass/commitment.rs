@@ -0,0 +1,134 @@
+use super::*;
+
+impl<P, F> ProjectiveVar<P, F>
+where
+    P: SWCurveConfig,
+    F: FieldVar<P::BaseField, BasePrimeField<P>>,
+    for<'a> &'a F: FieldOpsBounds<'a, P::BaseField, F>,
+{
+    /// Enforces that `rerand == orig + delta * h`, where `delta_bits` is a
+    /// little-endian bit representation of the scalar `delta`.
+    ///
+    /// Since `h` is a constant, `delta * h` is computed via fixed-base
+    /// scalar multiplication.
+    #[tracing::instrument(target = "gr1cs", skip(orig, rerand, delta_bits))]
+    pub fn enforce_rerandomization(
+        orig: &Self,
+        rerand: &Self,
+        delta_bits: &[Boolean<BasePrimeField<P>>],
+        h: SWAffine<P>,
+    ) -> Result<(), SynthesisError> {
+        let bases = powers_of_two_multiples(h, delta_bits.len());
+        let mut expected = orig.clone();
+        expected.precomputed_base_scalar_mul_le(delta_bits.iter().zip(&bases))?;
+        expected.enforce_equal(rerand)
+    }
+
+    /// Enforces that `commitment` is the Pedersen vector commitment
+    /// `sum_i values[i] * gs[i] + randomness * h`.
+    ///
+    /// The scalars are interpreted as integers via their canonical
+    /// little-endian bit decomposition, and the multi-scalar multiplication
+    /// uses precomputed multiples of the constant bases.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values` and `gs` have different lengths.
+    #[tracing::instrument(target = "gr1cs", skip(commitment, values, randomness, gs, h))]
+    pub fn enforce_pedersen_vector_commit(
+        commitment: &Self,
+        values: &[FpVar<BasePrimeField<P>>],
+        randomness: &FpVar<BasePrimeField<P>>,
+        gs: &[SWAffine<P>],
+        h: SWAffine<P>,
+    ) -> Result<(), SynthesisError> {
+        assert_eq!(values.len(), gs.len());
+        let num_bits = BasePrimeField::<P>::MODULUS_BIT_SIZE as usize;
+        let bases = gs
+            .iter()
+            .chain([&h])
+            .map(|g| powers_of_two_multiples(*g, num_bits))
+            .collect::<Vec<_>>();
+        let expected =
+            Self::precomputed_base_multiscalar_mul_le(&bases, values.iter().chain([randomness]))?;
+        expected.enforce_equal(commitment)
+    }
+}
+
+/// Outputs `[base, 2 * base, ..., 2^(n - 1) * base]`, as expected by
+/// `CurveVar::precomputed_base_scalar_mul_le`.
+pub(super) fn powers_of_two_multiples<P: SWCurveConfig>(
+    base: SWAffine<P>,
+    n: usize,
+) -> Vec<SWProjective<P>> {
+    let mut cur = SWProjective::from(base);
+    (0..n)
+        .map(|_| {
+            let result = cur;
+            cur.double_in_place();
+            result
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        alloc::AllocVar,
+        convert::ToBitsGadget,
+        fields::{emulated_fp::EmulatedFpVar, fp::FpVar},
+        groups::curves::short_weierstrass::ProjectiveVar,
+    };
+    use ark_bls12_381::{g1::Config, Fq, Fr, G1Projective};
+    use ark_ec::CurveGroup;
+    use ark_relations::gr1cs::ConstraintSystem;
+    use ark_std::UniformRand;
+
+    type G1Var = ProjectiveVar<Config, FpVar<Fq>>;
+
+    #[test]
+    fn test_enforce_rerandomization() {
+        let mut rng = ark_std::test_rng();
+        let h = G1Projective::rand(&mut rng).into_affine();
+        let orig = G1Projective::rand(&mut rng);
+        let delta = Fr::rand(&mut rng);
+        let rerand = orig + h * delta;
+
+        for (claimed_delta, expected) in [(delta, true), (delta + Fr::from(1u64), false)] {
+            let cs = ConstraintSystem::new_ref();
+            let orig = G1Var::new_witness(cs.clone(), || Ok(orig)).unwrap();
+            let rerand = G1Var::new_witness(cs.clone(), || Ok(rerand)).unwrap();
+            let delta =
+                EmulatedFpVar::<Fr, Fq>::new_witness(cs.clone(), || Ok(claimed_delta)).unwrap();
+            let delta_bits = delta.to_bits_le().unwrap();
+            G1Var::enforce_rerandomization(&orig, &rerand, &delta_bits, h).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_enforce_pedersen_vector_commit() {
+        let mut rng = ark_std::test_rng();
+        let gs = [(); 3].map(|_| G1Projective::rand(&mut rng).into_affine());
+        let h = G1Projective::rand(&mut rng).into_affine();
+        let values = [3u64, 141, 59];
+        let randomness = 26535u64;
+        let commitment = gs
+            .iter()
+            .zip(values)
+            .map(|(g, v)| *g * Fr::from(v))
+            .sum::<G1Projective>()
+            + h * Fr::from(randomness);
+
+        for (opened, expected) in [([3u64, 141, 59], true), ([3, 142, 59], false)] {
+            let cs = ConstraintSystem::new_ref();
+            let commitment = G1Var::new_witness(cs.clone(), || Ok(commitment)).unwrap();
+            let values =
+                opened.map(|v| FpVar::new_witness(cs.clone(), || Ok(Fq::from(v))).unwrap());
+            let randomness = FpVar::new_witness(cs.clone(), || Ok(Fq::from(randomness))).unwrap();
+            G1Var::enforce_pedersen_vector_commit(&commitment, &values, &randomness, &gs, h)
+                .unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+}
@@ -4,15 +4,16 @@ use ark_ec::{
 };
 use ark_ff::{AdditiveGroup, BitIteratorBE, Field, One, PrimeField, Zero};
 use ark_relations::gr1cs::{ConstraintSystemRef, Namespace, SynthesisError};
-use ark_std::{borrow::Borrow, marker::PhantomData, ops::Mul};
+use ark_std::{borrow::Borrow, iter::Sum, marker::PhantomData, ops::Mul};
 use educe::Educe;
 use non_zero_affine::NonZeroAffineVar;
+use num_bigint::BigUint;
 
 use crate::{
     convert::ToConstraintFieldGadget,
     fields::{emulated_fp::EmulatedFpVar, fp::FpVar},
     prelude::*,
-    Vec,
+    Assignment, Vec,
 };
 
 /// This module provides a generic implementation of G1 and G2 for
@@ -38,6 +39,9 @@ pub mod mnt6;
 /// elliptic curve points.
 pub mod non_zero_affine;
 
+mod commitment;
+mod serialize;
+
 type BasePrimeField<P> = <<P as CurveConfig>::BaseField as Field>::BasePrimeField;
 
 /// An implementation of arithmetic for Short Weierstrass curves that relies on
@@ -93,6 +97,43 @@ where
         }
     }
 
+    /// Allocates a new witness point, and enforces that it lies on the curve
+    /// and in the prime-order subgroup.
+    ///
+    /// This is the recommended way of allocating an affine point witness. It
+    /// allocates the point via [`ProjectiveVar::new_witness`], which performs
+    /// both checks, and then converts it to affine form.
+    #[tracing::instrument(target = "gr1cs", skip(cs, f))]
+    pub fn new_witness_checked<T: Borrow<SWAffine<P>>>(
+        cs: impl Into<Namespace<BasePrimeField<P>>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+    ) -> Result<Self, SynthesisError> {
+        let point = <ProjectiveVar<P, F> as AllocVar<SWAffine<P>, _>>::new_witness(cs, f)?;
+        point.to_affine()
+    }
+
+    /// Allocates the coordinates and the infinity flag of a new witness
+    /// point, without *any* checks.
+    ///
+    /// Neither the on-curve nor the subgroup check is performed, so this is
+    /// only safe if the caller enforces membership of the point by other
+    /// means. Otherwise, use [`Self::new_witness_checked`].
+    #[tracing::instrument(target = "gr1cs", skip(cs, f))]
+    pub fn new_witness_unchecked<T: Borrow<SWAffine<P>>>(
+        cs: impl Into<Namespace<BasePrimeField<P>>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+    ) -> Result<Self, SynthesisError> {
+        let ns = cs.into();
+        let cs = ns.cs();
+        let point = f().map(|p| *p.borrow()).ok();
+        let x = F::new_witness(ark_relations::ns!(cs, "x"), || point.map(|p| p.x).get())?;
+        let y = F::new_witness(ark_relations::ns!(cs, "y"), || point.map(|p| p.y).get())?;
+        let infinity = Boolean::new_witness(ark_relations::ns!(cs, "infinity"), || {
+            point.map(|p| p.infinity).get()
+        })?;
+        Ok(Self::new(x, y, infinity))
+    }
+
     /// Returns the value assigned to `self` in the underlying
     /// constraint system.
     pub fn value(&self) -> Result<SWAffine<P>, SynthesisError> {
@@ -101,6 +142,38 @@ where
             false => SWAffine::new(self.x.value()?, self.y.value()?),
         })
     }
+
+    /// Outputs the bit `self == 0`, i.e. whether `self` is the point at
+    /// infinity.
+    ///
+    /// This is just the `infinity` flag, which [`ProjectiveVar::to_affine`]
+    /// sets exactly when [`ProjectiveVar::is_zero`] holds. Hence, this does
+    /// not create any constraints.
+    pub fn is_zero(&self) -> Result<Boolean<BasePrimeField<P>>, SynthesisError> {
+        Ok(self.infinity.clone())
+    }
+
+    /// Enforces that `a[i] == b[i]` for every `i`.
+    ///
+    /// Two points are equal if both are the point at infinity, regardless of
+    /// their coordinates, or if neither is and their coordinates agree.
+    ///
+    /// Returns `SynthesisError::Unsatisfiable` if `a` and `b` have different
+    /// lengths.
+    #[tracing::instrument(target = "gr1cs", skip(a, b))]
+    pub fn enforce_all_points_equal(a: &[Self], b: &[Self]) -> Result<(), SynthesisError> {
+        if a.len() != b.len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        for (p, q) in a.iter().zip(b) {
+            p.infinity.enforce_equal(&q.infinity)?;
+            // The coordinates of the point at infinity are arbitrary.
+            let is_finite = !&p.infinity;
+            p.x.conditional_enforce_equal(&q.x, &is_finite)?;
+            p.y.conditional_enforce_equal(&q.y, &is_finite)?;
+        }
+        Ok(())
+    }
 }
 
 impl<P, F> ToConstraintFieldGadget<BasePrimeField<P>> for AffineVar<P, F>
@@ -121,6 +194,33 @@ where
     }
 }
 
+impl<P, F> CondSelectGadget<BasePrimeField<P>> for AffineVar<P, F>
+where
+    P: SWCurveConfig,
+    F: FieldVar<P::BaseField, BasePrimeField<P>>,
+    for<'a> &'a F: FieldOpsBounds<'a, P::BaseField, F>,
+{
+    #[inline]
+    #[tracing::instrument(target = "gr1cs")]
+    fn conditionally_select(
+        cond: &Boolean<BasePrimeField<P>>,
+        true_value: &Self,
+        false_value: &Self,
+    ) -> Result<Self, SynthesisError> {
+        match cond {
+            Boolean::Constant(true) => Ok(true_value.clone()),
+            Boolean::Constant(false) => Ok(false_value.clone()),
+            _ => {
+                let x = cond.select(&true_value.x, &false_value.x)?;
+                let y = cond.select(&true_value.y, &false_value.y)?;
+                let infinity = cond.select(&true_value.infinity, &false_value.infinity)?;
+
+                Ok(Self::new(x, y, infinity))
+            },
+        }
+    }
+}
+
 impl<P, F> GR1CSVar<BasePrimeField<P>> for ProjectiveVar<P, F>
 where
     P: SWCurveConfig,
@@ -238,8 +338,11 @@ where
 
     /// Mixed addition, which is useful when `other = (x2, y2)` is known to have
     /// z = 1.
+    ///
+    /// This requires 11 multiplications, one fewer than the complete addition
+    /// formula, as `other` is known not to be the point at infinity.
     #[tracing::instrument(target = "gr1cs", skip(self, other))]
-    pub(crate) fn add_mixed(&self, other: &NonZeroAffineVar<P, F>) -> Result<Self, SynthesisError> {
+    pub fn add_mixed(&self, other: &NonZeroAffineVar<P, F>) -> Result<Self, SynthesisError> {
         // Complete mixed addition formula from Renes-Costello-Batina 2015
         // Algorithm 2
         // (https://eprint.iacr.org/2015/1060).
@@ -278,6 +381,198 @@ where
         Ok(ProjectiveVar::new(x, y, z))
     }
 
+    /// Sets `self = self + self`, and returns `self`.
+    ///
+    /// If `self` is a constant, the doubling is computed natively and no
+    /// constraints are generated.
+    #[inline]
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn double_in_place(&mut self) -> Result<&mut Self, SynthesisError> {
+        if self.is_constant() {
+            let value = self.value()?;
+            // Doubling the identity leaves it unchanged.
+            if !value.is_zero() {
+                *self = Self::constant(value.double());
+            }
+            return Ok(self);
+        }
+        // Complete doubling formula from Renes-Costello-Batina 2015
+        // Algorithm 3
+        // (https://eprint.iacr.org/2015/1060).
+        // Below, comments at the end of a line denote the corresponding
+        // step(s) of the algorithm
+        //
+        // Adapted from code in
+        // https://github.com/RustCrypto/elliptic-curves/blob/master/p256/src/arithmetic/projective.rs
+        let three_b = P::COEFF_B.double() + &P::COEFF_B;
+
+        let xx = self.x.square()?; // 1
+        let yy = self.y.square()?; // 2
+        let zz = self.z.square()?; // 3
+        let xy2 = (&self.x * &self.y).double()?; // 4, 5
+        let xz2 = (&self.x * &self.z).double()?; // 6, 7
+
+        let axz2 = mul_by_coeff_a::<P, F>(&xz2); // 8
+
+        let bzz3_part = &axz2 + &zz * three_b; // 9, 10
+        let yy_m_bzz3 = &yy - &bzz3_part; // 11
+        let yy_p_bzz3 = &yy + &bzz3_part; // 12
+        let y_frag = yy_p_bzz3 * &yy_m_bzz3; // 13
+        let x_frag = yy_m_bzz3 * &xy2; // 14
+
+        let bxz3 = xz2 * three_b; // 15
+        let azz = mul_by_coeff_a::<P, F>(&zz); // 16
+        let b3_xz_pairs = mul_by_coeff_a::<P, F>(&(&xx - &azz)) + &bxz3; // 15, 16, 17, 18, 19
+        let xx3_p_azz = (xx.double()? + &xx + &azz) * &b3_xz_pairs; // 23, 24, 25
+
+        let y = y_frag + &xx3_p_azz; // 26, 27
+        let yz2 = (&self.y * &self.z).double()?; // 28, 29
+        let x = x_frag - &(b3_xz_pairs * &yz2); // 30, 31
+        let z = (yz2 * &yy).double()?.double()?; // 32, 33, 34
+        self.x = x;
+        self.y = y;
+        self.z = z;
+        Ok(self)
+    }
+
+    /// Computes the sum of `points`, starting from the identity and adding
+    /// each point with the complete addition formula.
+    ///
+    /// Returns the identity if `points` is empty.
+    #[tracing::instrument(target = "gr1cs", skip(points))]
+    pub fn sum<'a>(points: impl IntoIterator<Item = &'a Self>) -> Result<Self, SynthesisError>
+    where
+        Self: 'a,
+    {
+        Ok(points
+            .into_iter()
+            .fold(Self::zero(), |acc, point| acc + point))
+    }
+
+    /// The number of bits of the challenge accepted by
+    /// [`Self::batch_check_fixed_base`].
+    pub const BATCH_CHALLENGE_BITS: usize = 64;
+
+    /// Enforces that `results[i] == scalars[i] * g` for every `i`, where each
+    /// scalar is given by its little-endian bits.
+    ///
+    /// Rather than checking each scalar multiplication separately, this
+    /// checks the random linear combination
+    /// `sum_i r^i * results[i] == (sum_i r^i * scalars[i]) * g`, where `r` is
+    /// `challenge`. The left-hand side is evaluated via Horner's rule, which
+    /// requires one scalar multiplication by the
+    /// [`Self::BATCH_CHALLENGE_BITS`]-bit challenge per entry after the first.
+    /// The combined scalar is computed with native arithmetic, reducing it
+    /// modulo the scalar field after each step, so only a single fixed-base
+    /// scalar multiplication is needed on the right-hand side. A scalar
+    /// multiplication by a short challenge costs less than a fixed-base one by
+    /// a full scalar, so this is cheaper than checking each entry separately
+    /// once there are a handful of entries.
+    ///
+    /// `challenge` must be smaller than `2^Self::BATCH_CHALLENGE_BITS`, and
+    /// must be sampled after `results` are fixed (e.g. derived from a
+    /// transcript). If every `results[i]` lies in the prime-order subgroup,
+    /// a wrong batch is accepted for at most `results.len() - 1` values of
+    /// `challenge`. This bound does not hold for points with a component
+    /// outside the subgroup, which can cancel for some challenges; points
+    /// allocated as witnesses via [`AllocVar`] are checked to be in the
+    /// subgroup, but points allocated via
+    /// [`CurveVar::new_variable_omit_prime_order_check`] are not.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scalars` and `results` have different lengths, if a scalar
+    /// has more than `P::ScalarField::MODULUS_BIT_SIZE` bits, or if
+    /// `P::ScalarField::MODULUS_BIT_SIZE + Self::BATCH_CHALLENGE_BITS + 3` is
+    /// not smaller than the size of the constraint field, as the combined
+    /// scalar could then overflow the constraint field before being reduced.
+    #[tracing::instrument(target = "gr1cs", skip(scalars, results))]
+    pub fn batch_check_fixed_base(
+        g: SWAffine<P>,
+        scalars: &[Vec<Boolean<BasePrimeField<P>>>],
+        results: &[Self],
+        challenge: &FpVar<BasePrimeField<P>>,
+    ) -> Result<(), SynthesisError> {
+        let scalar_size = <P::ScalarField as PrimeField>::MODULUS_BIT_SIZE as usize;
+        assert_eq!(scalars.len(), results.len());
+        assert!(scalars.iter().all(|bits| bits.len() <= scalar_size));
+        assert!(
+            scalar_size + Self::BATCH_CHALLENGE_BITS + 3
+                < BasePrimeField::<P>::MODULUS_BIT_SIZE as usize
+        );
+        if results.is_empty() {
+            return Ok(());
+        }
+        let (challenge_bits, _) =
+            challenge.to_bits_le_with_top_bits_zero(Self::BATCH_CHALLENGE_BITS)?;
+        let modulus: BigUint = <P::ScalarField as PrimeField>::MODULUS.into();
+        let modulus_fp = BasePrimeField::<P>::from(modulus.clone());
+
+        let last = results.len() - 1;
+        let mut combined_bits = scalars[last].clone();
+        let mut combined_result = results[last].clone();
+        for i in (0..last).rev() {
+            // `sum < 2^(scalar_size + BATCH_CHALLENGE_BITS + 1)`, so the
+            // quotient by the scalar field modulus fits into
+            // `BATCH_CHALLENGE_BITS + 2` bits, and neither side of
+            // `sum == reduced + quotient * modulus` wraps around.
+            let sum = Boolean::le_bits_to_fp(&combined_bits)? * challenge
+                + Boolean::le_bits_to_fp(&scalars[i])?;
+            let mode = if sum.is_constant() {
+                AllocationMode::Constant
+            } else {
+                AllocationMode::Witness
+            };
+            let quotient = FpVar::new_variable(
+                ark_relations::ns!(sum.cs(), "quotient"),
+                || {
+                    let sum: BigUint = sum.value()?.into();
+                    Ok(BasePrimeField::<P>::from(sum / &modulus))
+                },
+                mode,
+            )?;
+            quotient.enforce_bit_size(Self::BATCH_CHALLENGE_BITS + 2)?;
+            let reduced = sum - quotient * modulus_fp;
+            combined_bits = reduced.to_bits_le_with_top_bits_zero(scalar_size)?.0;
+            combined_result = combined_result.scalar_mul_le(challenge_bits.iter())? + &results[i];
+        }
+
+        let expected = Self::fixed_base_scalar_mul_le(g, &combined_bits)?;
+        combined_result.enforce_equal(&expected)
+    }
+
+    /// Enforces that the Pedersen-committed amounts in `input_commits` and
+    /// `output_commits` balance up to `fee`, i.e. that
+    /// `sum(input_commits) - sum(output_commits) - fee * h == 0`, where `h` is
+    /// the generator that the amounts are committed under.
+    ///
+    /// This only balances the committed amounts if the blinding factors of the
+    /// inputs and outputs also balance, which the caller must arrange.
+    #[tracing::instrument(target = "gr1cs", skip(input_commits, output_commits))]
+    pub fn enforce_commitment_balance(
+        input_commits: &[Self],
+        output_commits: &[Self],
+        fee: &FpVar<BasePrimeField<P>>,
+        h: SWAffine<P>,
+    ) -> Result<(), SynthesisError> {
+        let fee_commit = Self::fixed_base_scalar_mul_le(h, &fee.to_bits_le()?)?;
+        let total_in = Self::sum(input_commits)?;
+        let total_out = Self::sum(output_commits)? + fee_commit;
+        total_in.enforce_equal(&total_out)
+    }
+
+    /// Outputs `scalar * g` for the constant base `g`, where `bits` are the
+    /// little-endian bits of `scalar`.
+    fn fixed_base_scalar_mul_le(
+        g: SWAffine<P>,
+        bits: &[Boolean<BasePrimeField<P>>],
+    ) -> Result<Self, SynthesisError> {
+        let bases = commitment::powers_of_two_multiples(g, bits.len());
+        let mut result = Self::zero();
+        result.precomputed_base_scalar_mul_le(bits.iter().zip(&bases))?;
+        Ok(result)
+    }
+
     /// Computes a scalar multiplication with a little-endian scalar of size
     /// `P::ScalarField::MODULUS_BITS`.
     #[tracing::instrument(
@@ -453,42 +748,7 @@ where
     #[inline]
     #[tracing::instrument(target = "gr1cs")]
     fn double_in_place(&mut self) -> Result<(), SynthesisError> {
-        // Complete doubling formula from Renes-Costello-Batina 2015
-        // Algorithm 3
-        // (https://eprint.iacr.org/2015/1060).
-        // Below, comments at the end of a line denote the corresponding
-        // step(s) of the algorithm
-        //
-        // Adapted from code in
-        // https://github.com/RustCrypto/elliptic-curves/blob/master/p256/src/arithmetic/projective.rs
-        let three_b = P::COEFF_B.double() + &P::COEFF_B;
-
-        let xx = self.x.square()?; // 1
-        let yy = self.y.square()?; // 2
-        let zz = self.z.square()?; // 3
-        let xy2 = (&self.x * &self.y).double()?; // 4, 5
-        let xz2 = (&self.x * &self.z).double()?; // 6, 7
-
-        let axz2 = mul_by_coeff_a::<P, F>(&xz2); // 8
-
-        let bzz3_part = &axz2 + &zz * three_b; // 9, 10
-        let yy_m_bzz3 = &yy - &bzz3_part; // 11
-        let yy_p_bzz3 = &yy + &bzz3_part; // 12
-        let y_frag = yy_p_bzz3 * &yy_m_bzz3; // 13
-        let x_frag = yy_m_bzz3 * &xy2; // 14
-
-        let bxz3 = xz2 * three_b; // 15
-        let azz = mul_by_coeff_a::<P, F>(&zz); // 16
-        let b3_xz_pairs = mul_by_coeff_a::<P, F>(&(&xx - &azz)) + &bxz3; // 15, 16, 17, 18, 19
-        let xx3_p_azz = (xx.double()? + &xx + &azz) * &b3_xz_pairs; // 23, 24, 25
-
-        let y = y_frag + &xx3_p_azz; // 26, 27
-        let yz2 = (&self.y * &self.z).double()?; // 28, 29
-        let x = x_frag - &(b3_xz_pairs * &yz2); // 30, 31
-        let z = (yz2 * &yy).double()?.double()?; // 32, 33, 34
-        self.x = x;
-        self.y = y;
-        self.z = z;
+        ProjectiveVar::double_in_place(self)?;
         Ok(())
     }
 
@@ -728,6 +988,28 @@ where
 {
 }
 
+impl<'a, P, F> Sum<&'a ProjectiveVar<P, F>> for ProjectiveVar<P, F>
+where
+    P: SWCurveConfig,
+    F: FieldVar<P::BaseField, BasePrimeField<P>>,
+    for<'b> &'b F: FieldOpsBounds<'b, P::BaseField, F>,
+{
+    fn sum<I: Iterator<Item = &'a ProjectiveVar<P, F>>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |acc, point| acc + point)
+    }
+}
+
+impl<P, F> Sum<ProjectiveVar<P, F>> for ProjectiveVar<P, F>
+where
+    P: SWCurveConfig,
+    F: FieldVar<P::BaseField, BasePrimeField<P>>,
+    for<'b> &'b F: FieldOpsBounds<'b, P::BaseField, F>,
+{
+    fn sum<I: Iterator<Item = ProjectiveVar<P, F>>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |acc, point| acc + point)
+    }
+}
+
 impl<P, F> CondSelectGadget<BasePrimeField<P>> for ProjectiveVar<P, F>
 where
     P: SWCurveConfig,
@@ -979,17 +1261,24 @@ where
 mod test_sw_curve {
     use crate::{
         alloc::AllocVar,
-        convert::ToBitsGadget,
+        boolean::Boolean,
+        convert::{ToBitsGadget, ToConstraintFieldGadget},
         eq::EqGadget,
         fields::{emulated_fp::EmulatedFpVar, fp::FpVar},
-        groups::{curves::short_weierstrass::ProjectiveVar, CurveVar},
+        groups::{
+            curves::short_weierstrass::{
+                non_zero_affine::NonZeroAffineVar, AffineVar, ProjectiveVar,
+            },
+            CurveVar,
+        },
+        GR1CSVar,
     };
     use ark_ec::{
         short_weierstrass::{Projective, SWCurveConfig},
-        CurveGroup,
+        AffineRepr, CurveGroup,
     };
-    use ark_ff::PrimeField;
-    use ark_relations::gr1cs::{ConstraintSystem, Result};
+    use ark_ff::{AdditiveGroup, BitIteratorLE, PrimeField};
+    use ark_relations::gr1cs::{ConstraintSystem, ConstraintSystemRef, Result, SynthesisError};
     use ark_std::UniformRand;
     use num_traits::Zero;
 
@@ -1031,4 +1320,350 @@ mod test_sw_curve {
         assert!(zero_point_scalar_mul_satisfied::<ark_mnt6_298::G1Projective>().unwrap());
         assert!(zero_point_scalar_mul_satisfied::<ark_bn254::G1Projective>().unwrap());
     }
+
+    fn add_mixed_matches_add<G>() -> Result<()>
+    where
+        G: CurveGroup,
+        G::BaseField: PrimeField,
+        G::Config: SWCurveConfig,
+    {
+        let mut rng = ark_std::test_rng();
+        let p = Projective::<G::Config>::rand(&mut rng);
+
+        for (a, b) in [
+            (Projective::rand(&mut rng), Projective::rand(&mut rng)),
+            (Projective::zero(), p),
+            (p, p),
+            (-p, p),
+        ] {
+            let cs = ConstraintSystem::new_ref();
+            let a_var =
+                ProjectiveVar::<G::Config, FpVar<G::BaseField>>::new_witness(cs.clone(), || Ok(a))?;
+            let b_var =
+                ProjectiveVar::<G::Config, FpVar<G::BaseField>>::new_witness(cs.clone(), || Ok(b))?;
+            let b_affine = b.into_affine();
+            let b_non_zero = NonZeroAffineVar::new(
+                FpVar::new_witness(cs.clone(), || Ok(b_affine.x))?,
+                FpVar::new_witness(cs.clone(), || Ok(b_affine.y))?,
+            );
+
+            let constraints = cs.num_constraints();
+            let mixed = a_var.add_mixed(&b_non_zero)?;
+            let mixed_cost = cs.num_constraints() - constraints;
+            let constraints = cs.num_constraints();
+            let full = &a_var + &b_var;
+            assert!(mixed_cost < cs.num_constraints() - constraints);
+            assert_eq!(mixed.value()?, a + b);
+            mixed.enforce_equal(&full)?;
+
+            let b_constant =
+                NonZeroAffineVar::new(FpVar::constant(b_affine.x), FpVar::constant(b_affine.y));
+            let mixed = a_var.add_mixed(&b_constant)?;
+            assert_eq!(mixed.value()?, a + b);
+            assert!(cs.is_satisfied()?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_double_in_place() {
+        type G1Var = ProjectiveVar<ark_bls12_381::g1::Config, FpVar<ark_bls12_381::Fq>>;
+        let mut rng = ark_std::test_rng();
+        let point = ark_bls12_381::G1Projective::rand(&mut rng);
+        let n = 5;
+
+        let cs = ConstraintSystem::new_ref();
+        let mut in_place = G1Var::new_witness(cs.clone(), || Ok(point)).unwrap();
+        for _ in 0..n {
+            in_place.double_in_place().unwrap();
+        }
+        let mut expected = point;
+        for _ in 0..n {
+            expected.double_in_place();
+        }
+        assert_eq!(in_place.value().unwrap(), expected);
+        assert!(cs.is_satisfied().unwrap());
+
+        let other_cs = ConstraintSystem::new_ref();
+        let mut doubled = G1Var::new_witness(other_cs.clone(), || Ok(point)).unwrap();
+        for _ in 0..n {
+            doubled = doubled.double().unwrap();
+        }
+        assert_eq!(doubled.value().unwrap(), expected);
+        assert_eq!(cs.num_constraints(), other_cs.num_constraints());
+
+        // Doubling a constant identity is a no-op.
+        let mut zero = G1Var::zero();
+        zero.double_in_place().unwrap().double_in_place().unwrap();
+        assert!(zero.value().unwrap().is_zero());
+        assert!(zero.is_constant());
+    }
+
+    #[test]
+    fn test_affine_to_sponge_field_elements() {
+        type G1Var = ProjectiveVar<ark_bls12_381::g1::Config, FpVar<ark_bls12_381::Fq>>;
+        let mut rng = ark_std::test_rng();
+        for point in [
+            ark_bls12_381::G1Projective::rand(&mut rng),
+            ark_bls12_381::G1Projective::zero(),
+        ] {
+            let cs = ConstraintSystem::new_ref();
+            let affine = G1Var::new_witness(cs.clone(), || Ok(point))
+                .unwrap()
+                .to_affine()
+                .unwrap();
+            let elements = affine.to_sponge_field_elements().unwrap();
+            // Native absorption of a short Weierstrass point absorbs its
+            // coordinates followed by the infinity flag.
+            let point = point.into_affine();
+            let expected = vec![point.x, point.y, ark_bls12_381::Fq::from(point.infinity)];
+            assert_eq!(elements.value().unwrap(), expected);
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_affine_conditionally_select() {
+        type G1Var = ProjectiveVar<ark_bls12_381::g1::Config, FpVar<ark_bls12_381::Fq>>;
+        let mut rng = ark_std::test_rng();
+        let point = ark_bls12_381::G1Projective::rand(&mut rng);
+        let zero = ark_bls12_381::G1Projective::zero();
+        for cond in [true, false] {
+            let cs = ConstraintSystem::new_ref();
+            let a = G1Var::new_witness(cs.clone(), || Ok(point))
+                .unwrap()
+                .to_affine()
+                .unwrap();
+            let b = G1Var::new_witness(cs.clone(), || Ok(zero))
+                .unwrap()
+                .to_affine()
+                .unwrap();
+            let cond_var = Boolean::new_witness(cs.clone(), || Ok(cond)).unwrap();
+            let selected = cond_var.select(&a, &b).unwrap();
+            let expected = if cond { point } else { zero };
+            assert_eq!(selected.value().unwrap(), expected.into_affine());
+            assert_eq!(selected.infinity.value().unwrap(), !cond);
+            assert!(cs.is_satisfied().unwrap());
+
+            let selected = Boolean::constant(cond).select(&a, &b).unwrap();
+            assert_eq!(selected.value().unwrap(), expected.into_affine());
+        }
+    }
+
+    #[test]
+    fn test_sum() {
+        type G1Var = ProjectiveVar<ark_bls12_381::g1::Config, FpVar<ark_bls12_381::Fq>>;
+        let mut rng = ark_std::test_rng();
+        let random = (0..5)
+            .map(|_| ark_bls12_381::G1Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let mut cancelling = random.clone();
+        cancelling[3] = -cancelling[1];
+        for points in [random, cancelling] {
+            let cs = ConstraintSystem::new_ref();
+            let vars = points
+                .iter()
+                .map(|p| G1Var::new_witness(cs.clone(), || Ok(*p)).unwrap())
+                .collect::<Vec<_>>();
+            let expected = points.iter().sum::<ark_bls12_381::G1Projective>();
+            assert_eq!(G1Var::sum(&vars).unwrap().value().unwrap(), expected);
+            assert_eq!(vars.iter().sum::<G1Var>().value().unwrap(), expected);
+            assert_eq!(vars.into_iter().sum::<G1Var>().value().unwrap(), expected);
+            assert!(cs.is_satisfied().unwrap());
+        }
+
+        assert!(G1Var::sum(&[]).unwrap().value().unwrap().is_zero());
+    }
+
+    #[test]
+    fn test_is_zero() {
+        type G1Var = ProjectiveVar<ark_bls12_381::g1::Config, FpVar<ark_bls12_381::Fq>>;
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::new_ref();
+        let point = ark_bls12_381::G1Projective::rand(&mut rng);
+        let p = G1Var::new_witness(cs.clone(), || Ok(point)).unwrap();
+        let q = G1Var::new_witness(cs.clone(), || Ok(point)).unwrap();
+        let difference = &p - &q;
+        for (var, expected) in [(&difference, true), (&p, false)] {
+            assert_eq!(var.is_zero().unwrap().value().unwrap(), expected);
+            let affine = var.to_affine().unwrap();
+            assert_eq!(affine.is_zero().unwrap().value().unwrap(), expected);
+        }
+        let constant = G1Var::constant(point - point);
+        assert!(constant.is_zero().unwrap().value().unwrap());
+        let affine = constant.to_affine().unwrap();
+        assert!(affine.is_zero().unwrap().value().unwrap());
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_enforce_all_points_equal() {
+        use ark_bls12_381::{g1::Config, Fq, G1Projective};
+        type G1Var = ProjectiveVar<Config, FpVar<Fq>>;
+        type G1AffineVar = AffineVar<Config, FpVar<Fq>>;
+        let mut rng = ark_std::test_rng();
+        let mut points = (0..3)
+            .map(|_| G1Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        points[1] = G1Projective::zero();
+        let mut other = points.clone();
+        other[1] = G1Projective::rand(&mut rng);
+
+        let satisfied = |a: &[G1Projective], b: &[G1Projective]| {
+            let cs = ConstraintSystem::new_ref();
+            let alloc = |points: &[G1Projective]| {
+                points
+                    .iter()
+                    .map(|p| {
+                        let p = G1Var::new_witness(cs.clone(), || Ok(*p)).unwrap();
+                        p.to_affine().unwrap()
+                    })
+                    .collect::<Vec<_>>()
+            };
+            G1AffineVar::enforce_all_points_equal(&alloc(a), &alloc(b)).unwrap();
+            cs.is_satisfied().unwrap()
+        };
+        assert!(satisfied(&points, &points));
+        assert!(!satisfied(&points, &other));
+        assert!(!satisfied(&other, &points));
+
+        // The coordinates of the point at infinity are ignored.
+        let cs = ConstraintSystem::new_ref();
+        let x = FpVar::new_witness(cs.clone(), || Ok(Fq::rand(&mut rng))).unwrap();
+        let y = FpVar::new_witness(cs.clone(), || Ok(Fq::rand(&mut rng))).unwrap();
+        let infinity = Boolean::new_witness(cs.clone(), || Ok(true)).unwrap();
+        let p = G1AffineVar::new(x, y, infinity);
+        let zero = G1Var::new_witness(cs.clone(), || Ok(G1Projective::zero())).unwrap();
+        let zero = zero.to_affine().unwrap();
+        G1AffineVar::enforce_all_points_equal(&[p.clone()], &[zero.clone()]).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+
+        let mismatch = G1AffineVar::enforce_all_points_equal(&[p], &[zero.clone(), zero]);
+        assert!(matches!(mismatch, Err(SynthesisError::Unsatisfiable)));
+    }
+
+    #[test]
+    fn test_new_witness_checked() {
+        use ark_bls12_381::{g1::Config, Fq, G1Affine, G1Projective};
+        type G1AffineVar = AffineVar<Config, FpVar<Fq>>;
+        let mut rng = ark_std::test_rng();
+        let on_curve = G1Projective::rand(&mut rng).into_affine();
+        // Changing `y` moves the point off the curve.
+        let off_curve = G1Affine::new_unchecked(on_curve.x, on_curve.y + Fq::from(1u64));
+        assert!(!off_curve.is_on_curve());
+
+        for point in [on_curve, G1Affine::identity()] {
+            let cs = ConstraintSystem::new_ref();
+            let checked = G1AffineVar::new_witness_checked(cs.clone(), || Ok(point)).unwrap();
+            let unchecked = G1AffineVar::new_witness_unchecked(cs.clone(), || Ok(point)).unwrap();
+            assert_eq!(checked.value().unwrap(), point);
+            assert_eq!(unchecked.value().unwrap(), point);
+            assert!(cs.is_satisfied().unwrap());
+        }
+
+        let cs = ConstraintSystem::new_ref();
+        G1AffineVar::new_witness_checked(cs.clone(), || Ok(off_curve)).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+
+        let cs = ConstraintSystem::new_ref();
+        let unchecked = G1AffineVar::new_witness_unchecked(cs.clone(), || Ok(off_curve)).unwrap();
+        assert_eq!(unchecked.x.value().unwrap(), off_curve.x);
+        assert_eq!(unchecked.y.value().unwrap(), off_curve.y);
+        assert_eq!(cs.num_constraints(), 1);
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_batch_check_fixed_base() {
+        type G1Var = ProjectiveVar<ark_bls12_381::g1::Config, FpVar<ark_bls12_381::Fq>>;
+        use ark_bls12_381::{Fq, Fr, G1Projective};
+        let mut rng = ark_std::test_rng();
+        let g = G1Projective::rand(&mut rng).into_affine();
+        let scalars = (0..4).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let challenge = Fq::from(u64::rand(&mut rng));
+        let alloc = |cs: ConstraintSystemRef<Fq>, wrong: Option<usize>| {
+            let scalar_vars = scalars
+                .iter()
+                .map(|s| {
+                    let bits = BitIteratorLE::new(s.into_bigint())
+                        .take(Fr::MODULUS_BIT_SIZE as usize)
+                        .collect::<Vec<_>>();
+                    Vec::<Boolean<_>>::new_witness(cs.clone(), || Ok(bits)).unwrap()
+                })
+                .collect::<Vec<_>>();
+            let result_vars = scalars
+                .iter()
+                .enumerate()
+                .map(|(i, s)| {
+                    let mut result = g * s;
+                    if wrong == Some(i) {
+                        result += g;
+                    }
+                    G1Var::new_witness(cs.clone(), || Ok(result)).unwrap()
+                })
+                .collect::<Vec<_>>();
+            (scalar_vars, result_vars)
+        };
+
+        let mut batch_cost = 0;
+        for wrong in [None, Some(1)] {
+            let cs = ConstraintSystem::new_ref();
+            let (scalar_vars, result_vars) = alloc(cs.clone(), wrong);
+            let challenge = FpVar::new_witness(cs.clone(), || Ok(challenge)).unwrap();
+            let constraints = cs.num_constraints();
+            G1Var::batch_check_fixed_base(g, &scalar_vars, &result_vars, &challenge).unwrap();
+            batch_cost = cs.num_constraints() - constraints;
+            assert_eq!(cs.is_satisfied().unwrap(), wrong.is_none());
+        }
+
+        // Checking each scalar multiplication separately costs more.
+        let cs = ConstraintSystem::new_ref();
+        let (scalar_vars, result_vars) = alloc(cs.clone(), None);
+        let constraints = cs.num_constraints();
+        for (bits, result) in scalar_vars.iter().zip(&result_vars) {
+            G1Var::fixed_base_scalar_mul_le(g, bits)
+                .unwrap()
+                .enforce_equal(result)
+                .unwrap();
+        }
+        assert!(cs.is_satisfied().unwrap());
+        assert!(batch_cost < cs.num_constraints() - constraints);
+    }
+
+    #[test]
+    fn test_enforce_commitment_balance() {
+        type G1Var = ProjectiveVar<ark_bls12_381::g1::Config, FpVar<ark_bls12_381::Fq>>;
+        use ark_bls12_381::{Fq, Fr, G1Projective};
+        let mut rng = ark_std::test_rng();
+        let h = G1Projective::rand(&mut rng).into_affine();
+        let k = G1Projective::rand(&mut rng).into_affine();
+        let commit = |amount: u64, blinding: Fr| h * Fr::from(amount) + k * blinding;
+        let (b1, b2) = (Fr::rand(&mut rng), Fr::rand(&mut rng));
+        let inputs = [commit(100, b1), commit(50, b2)];
+        let outputs = [
+            commit(120, b1 + b2 - Fr::from(1u64)),
+            commit(25, Fr::from(1u64)),
+        ];
+
+        for (fee, expected) in [(5u64, true), (6, false)] {
+            let cs = ConstraintSystem::new_ref();
+            let alloc = |points: &[G1Projective]| {
+                points
+                    .iter()
+                    .map(|p| G1Var::new_witness(cs.clone(), || Ok(*p)).unwrap())
+                    .collect::<Vec<_>>()
+            };
+            let (input_vars, output_vars) = (alloc(&inputs), alloc(&outputs));
+            let fee = FpVar::new_input(cs.clone(), || Ok(Fq::from(fee))).unwrap();
+            G1Var::enforce_commitment_balance(&input_vars, &output_vars, &fee, h).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_add_mixed() {
+        add_mixed_matches_add::<ark_bls12_381::G1Projective>().unwrap();
+        add_mixed_matches_add::<ark_pallas::Projective>().unwrap();
+        add_mixed_matches_add::<ark_bn254::G1Projective>().unwrap();
+    }
 }
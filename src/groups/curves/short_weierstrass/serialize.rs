@@ -0,0 +1,104 @@
+use super::*;
+
+impl<P, F> AffineVar<P, FpVar<F>>
+where
+    P: SWCurveConfig<BaseField = F>,
+    F: PrimeField,
+{
+    /// Outputs the uncompressed encoding of `self`, matching the default
+    /// `CanonicalSerialize::serialize_uncompressed` implementation for short
+    /// Weierstrass affine points.
+    ///
+    /// The encoding consists of the little-endian bytes of `x`, followed by
+    /// the little-endian bytes of `y`, padded so that the last byte has two
+    /// free bits for the flags. The top bit is set if `y > -y`, i.e. if `y`
+    /// is larger than `(p - 1) / 2`, and the bit below it is set if `self` is
+    /// the point at infinity, in which case both coordinates are encoded as
+    /// zero.
+    ///
+    /// Curves that override the default serialization, such as BLS12-381,
+    /// use a different encoding.
+    #[tracing::instrument(target = "gr1cs")]
+    pub fn to_bytes_uncompressed(&self) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        let modulus_bits = F::MODULUS_BIT_SIZE as usize;
+        let x_len = modulus_bits.div_ceil(8);
+        let y_len = (modulus_bits + 2).div_ceil(8);
+
+        let zero = FpVar::zero();
+        let x = self.infinity.select(&zero, &self.x)?;
+        let y = self.infinity.select(&zero, &self.y)?;
+        let mut x_bits = x.to_bits_le()?;
+        let mut y_bits = y.to_bits_le()?;
+        let half = F::from_bigint(F::MODULUS_MINUS_ONE_DIV_TWO).unwrap();
+        // `y > (p - 1) / 2` if and only if `y >= (p + 1) / 2`.
+        let y_is_negative = !Boolean::le_bits_lt_constant(&y_bits, (half + F::one()).into_bigint());
+
+        x_bits.resize(8 * x_len, Boolean::FALSE);
+        y_bits.resize(8 * y_len, Boolean::FALSE);
+        // `y < 2^modulus_bits`, so the flag bits are zero before this.
+        y_bits[8 * y_len - 2] = self.infinity.clone();
+        y_bits[8 * y_len - 1] = y_is_negative;
+        Ok(x_bits
+            .chunks(8)
+            .chain(y_bits.chunks(8))
+            .map(UInt8::from_bits_le)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        alloc::AllocVar,
+        fields::fp::FpVar,
+        groups::{curves::short_weierstrass::ProjectiveVar, CurveVar},
+        GR1CSVar,
+    };
+    use ark_ec::{
+        short_weierstrass::{Projective, SWCurveConfig},
+        CurveGroup,
+    };
+    use ark_ff::{AdditiveGroup, PrimeField};
+    use ark_relations::gr1cs::ConstraintSystem;
+    use ark_serialize::CanonicalSerialize;
+    use ark_std::UniformRand;
+
+    fn check_to_bytes_uncompressed<P>()
+    where
+        P: SWCurveConfig,
+        P::BaseField: PrimeField,
+    {
+        let mut rng = ark_std::test_rng();
+        let points = [
+            Projective::<P>::rand(&mut rng),
+            -Projective::<P>::rand(&mut rng),
+            Projective::<P>::ZERO,
+        ];
+        for point in points {
+            let cs = ConstraintSystem::new_ref();
+            let point_var =
+                ProjectiveVar::<P, FpVar<P::BaseField>>::new_witness(cs.clone(), || Ok(point))
+                    .unwrap();
+            let bytes = point_var
+                .to_affine()
+                .unwrap()
+                .to_bytes_uncompressed()
+                .unwrap();
+            let mut expected = Vec::new();
+            point
+                .into_affine()
+                .serialize_uncompressed(&mut expected)
+                .unwrap();
+            assert_eq!(bytes.value().unwrap(), expected);
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_to_bytes_uncompressed() {
+        // The base field of BLS12-377 leaves room for the flags in the last
+        // byte of `y`, while the base field of Pallas does not.
+        check_to_bytes_uncompressed::<ark_bls12_377::g1::Config>();
+        check_to_bytes_uncompressed::<ark_pallas::PallasConfig>();
+    }
+}
@@ -51,6 +51,9 @@ pub mod cmp;
 /// Utilities for converting variables to other kinds of variables.
 pub mod convert;
 
+/// The number of constraints generated by common gadget operations.
+pub mod cost;
+
 /// Utilities for checking equality of variables.
 pub mod eq;
 
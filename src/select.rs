@@ -106,4 +106,22 @@ pub trait ThreeBitCondNegLookupGadget<ConstraintF: Field>: Sized {
         b0b1: &Boolean<ConstraintF>,
         constants: &[Self::TableConstant],
     ) -> Result<Self, SynthesisError>;
+
+    /// Like [`Self::three_bit_cond_neg_lookup`], but computes the product
+    /// `b0b1 = bits[0] & bits[1]` internally instead of trusting the caller
+    /// to supply it.
+    ///
+    /// This costs one more constraint than the manual version, for the AND.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `bits.len() != 3` or `constants.len() != 4`.
+    fn three_bit_cond_neg_lookup_auto(
+        bits: &[Boolean<ConstraintF>],
+        constants: &[Self::TableConstant],
+    ) -> Result<Self, SynthesisError> {
+        assert_eq!(bits.len(), 3);
+        let b0b1 = &bits[0] & &bits[1];
+        Self::three_bit_cond_neg_lookup(bits, &b0b1, constants)
+    }
 }
@@ -1,5 +1,8 @@
 use core::iter;
 
+use ark_ff::Field;
+use ark_relations::gr1cs::ConstraintSystemRef;
+
 use crate::alloc::AllocationMode;
 
 pub(crate) fn modes() -> impl Iterator<Item = AllocationMode> {
@@ -13,3 +16,8 @@ pub(crate) fn combination<T: Clone>(
     iter::from_fn(move || i.next().map(|t| modes().map(move |mode| (mode, t.clone()))))
         .flat_map(|x| x)
 }
+
+/// Returns the number of linear combinations created so far in `cs`.
+pub(crate) fn num_lcs<F: Field>(cs: &ConstraintSystemRef<F>) -> usize {
+    cs.borrow().map_or(0, |cs| cs.num_linear_combinations)
+}
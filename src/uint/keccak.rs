@@ -0,0 +1,63 @@
+use super::*;
+
+impl<F: Field> UInt<64, u64, F> {
+    /// Applies the θ (theta) step of the Keccak-f\[1600\] permutation to the
+    /// state `lanes`, where the lane at column `x` and row `y` is
+    /// `lanes[x + 5 * y]`.
+    ///
+    /// Each lane is XORed with the parities of two neighbouring columns:
+    /// `A[x, y] ^= C[x - 1] ^ rotl(C[x + 1], 1)`, where `C[x]` is the XOR of
+    /// all lanes in column `x`.
+    #[tracing::instrument(target = "gr1cs", skip(lanes))]
+    pub fn keccak_theta(lanes: &[Self; 25]) -> Result<[Self; 25], SynthesisError> {
+        let parities: [Self; 5] = core::array::from_fn(|x| {
+            (1..5).fold(lanes[x].clone(), |acc, y| acc ^ &lanes[x + 5 * y])
+        });
+        let mixes: [Self; 5] =
+            core::array::from_fn(|x| &parities[(x + 4) % 5] ^ parities[(x + 1) % 5].rotate_left(1));
+        Ok(core::array::from_fn(|i| &lanes[i] ^ &mixes[i % 5]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{alloc::AllocVar, GR1CSVar};
+    use ark_relations::gr1cs::ConstraintSystem;
+    use ark_std::rand::Rng;
+    use ark_test_curves::bls12_381::Fr;
+
+    fn native_theta(lanes: [u64; 25]) -> [u64; 25] {
+        let mut parities = [0u64; 5];
+        for (i, lane) in lanes.iter().enumerate() {
+            parities[i % 5] ^= lane;
+        }
+        let mut result = lanes;
+        for (i, lane) in result.iter_mut().enumerate() {
+            let x = i % 5;
+            *lane ^= parities[(x + 4) % 5] ^ parities[(x + 1) % 5].rotate_left(1);
+        }
+        result
+    }
+
+    #[test]
+    fn test_keccak_theta() {
+        let mut rng = ark_std::test_rng();
+        let mut single_bit = [0u64; 25];
+        single_bit[7] = 1;
+        let random: [u64; 25] = core::array::from_fn(|_| rng.gen());
+        for state in [[0u64; 25], single_bit, random] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let lanes = <[UInt<64, u64, Fr>; 25]>::new_witness(cs.clone(), || Ok(state)).unwrap();
+            let result = UInt::keccak_theta(&lanes).unwrap();
+            assert_eq!(result.value().unwrap(), native_theta(state));
+            assert!(cs.is_satisfied().unwrap());
+        }
+        // A single set bit in lane (2, 1) flips 11 bits of the state.
+        let flipped: u32 = native_theta(single_bit)
+            .iter()
+            .map(|l| l.count_ones())
+            .sum();
+        assert_eq!(flipped, 11);
+    }
+}
@@ -10,6 +10,7 @@ mod and;
 mod cmp;
 mod convert;
 mod eq;
+mod keccak;
 mod not;
 mod or;
 mod rotate;
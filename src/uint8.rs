@@ -72,6 +72,39 @@ impl<F: Field> UInt8<F> {
     }
 }
 
+impl<F: PrimeField> UInt8<F> {
+    /// Outputs `Boolean::TRUE` if the byte strings `a` and `b` are equal, and
+    /// `Boolean::FALSE` otherwise.
+    ///
+    /// Unlike [`EqGadget::is_eq`] on slices, this does not require `a` and
+    /// `b` to have the same length: if their lengths differ, the output is
+    /// the constant `Boolean::FALSE`, and no constraints are generated.
+    #[tracing::instrument(target = "gr1cs", skip(a, b))]
+    pub fn bytes_are_equal(a: &[Self], b: &[Self]) -> Result<Boolean<F>, SynthesisError> {
+        if a.len() != b.len() {
+            return Ok(Boolean::FALSE);
+        }
+        a.is_eq(b)
+    }
+
+    /// If `condition == true`, enforces that the byte strings `a` and `b` are
+    /// equal.
+    ///
+    /// If the lengths of `a` and `b` differ, they can never be equal, so this
+    /// instead enforces that `condition == false`.
+    #[tracing::instrument(target = "gr1cs", skip(a, b))]
+    pub fn conditional_enforce_equal_bytes(
+        a: &[Self],
+        b: &[Self],
+        condition: &Boolean<F>,
+    ) -> Result<(), SynthesisError> {
+        if a.len() != b.len() {
+            return condition.enforce_equal(&Boolean::FALSE);
+        }
+        a.conditional_enforce_equal(b, condition)
+    }
+}
+
 /// Parses the `Vec<UInt8<ConstraintF>>` in fixed-sized
 /// `ConstraintF::MODULUS_BIT_SIZE - 1` chunks and converts each chunk, which is
 /// assumed to be little-endian, to its `FpVar<ConstraintF>` representation.
@@ -85,6 +118,23 @@ impl<ConstraintF: PrimeField> ToConstraintFieldGadget<ConstraintF> for [UInt8<Co
             .map(|chunk| Boolean::le_bits_to_fp(chunk.to_bits_le()?.as_slice()))
             .collect::<Result<Vec<_>, SynthesisError>>()
     }
+
+    /// Prefixes `self` with its length as 8 little-endian bytes before
+    /// packing, as in `Absorb::batch_to_sponge_field_elements` for `u8`.
+    #[tracing::instrument(target = "gr1cs")]
+    fn to_sponge_field_elements(&self) -> Result<Vec<FpVar<ConstraintF>>, SynthesisError> {
+        let mut bytes = UInt8::constant_vec(&(self.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(self);
+        bytes.to_constraint_field()
+    }
+}
+
+/// Converts a single byte to one `FpVar<ConstraintF>`.
+impl<ConstraintF: PrimeField> ToConstraintFieldGadget<ConstraintF> for UInt8<ConstraintF> {
+    #[tracing::instrument(target = "gr1cs")]
+    fn to_constraint_field(&self) -> Result<Vec<FpVar<ConstraintF>>, SynthesisError> {
+        ark_std::slice::from_ref(self).to_constraint_field()
+    }
 }
 
 impl<ConstraintF: PrimeField> ToConstraintFieldGadget<ConstraintF> for Vec<UInt8<ConstraintF>> {
@@ -92,6 +142,11 @@ impl<ConstraintF: PrimeField> ToConstraintFieldGadget<ConstraintF> for Vec<UInt8
     fn to_constraint_field(&self) -> Result<Vec<FpVar<ConstraintF>>, SynthesisError> {
         self.as_slice().to_constraint_field()
     }
+
+    #[tracing::instrument(target = "gr1cs")]
+    fn to_sponge_field_elements(&self) -> Result<Vec<FpVar<ConstraintF>>, SynthesisError> {
+        self.as_slice().to_sponge_field_elements()
+    }
 }
 
 #[cfg(test)]
@@ -211,6 +266,39 @@ mod test {
         Ok(())
     }
 
+    /// Checks `UInt8::bytes_are_equal(a, b) == eq`, and whether enforcing
+    /// equality under `condition` is satisfied.
+    fn check_bytes_are_equal(a: &[u8], b: &[u8], condition: bool, eq: bool, satisfied: bool) {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let a = Vec::<UInt8<Fr>>::new_witness(cs.clone(), || Ok(a.to_vec())).unwrap();
+        let b = Vec::<UInt8<Fr>>::new_witness(cs.clone(), || Ok(b.to_vec())).unwrap();
+        let condition = Boolean::new_witness(cs.clone(), || Ok(condition)).unwrap();
+        assert_eq!(UInt8::bytes_are_equal(&a, &b).unwrap().value().unwrap(), eq);
+        UInt8::conditional_enforce_equal_bytes(&a, &b, &condition).unwrap();
+        assert_eq!(cs.is_satisfied().unwrap(), satisfied);
+    }
+
+    #[test]
+    fn test_bytes_are_equal() {
+        let a = [1u8, 2, 3, 4];
+        check_bytes_are_equal(&a, &a, true, true, true);
+        check_bytes_are_equal(&[], &[], true, true, true);
+
+        let b = [1u8, 2, 3, 5];
+        check_bytes_are_equal(&a, &b, false, false, true);
+        check_bytes_are_equal(&a, &b, true, false, false);
+
+        // Byte strings of different lengths are never equal.
+        let c = [1u8, 2, 3];
+        check_bytes_are_equal(&a, &c, false, false, true);
+        check_bytes_are_equal(&a, &c, true, false, false);
+        let a_var = a.map(UInt8::<Fr>::constant);
+        let c_var = c.map(UInt8::<Fr>::constant);
+        let eq = UInt8::bytes_are_equal(&a_var, &c_var).unwrap();
+        assert!(eq.is_constant());
+        assert!(!eq.value().unwrap());
+    }
+
     #[test]
     fn test_uint8_to_constraint_field() -> Result<(), SynthesisError> {
         let mut rng = ark_std::test_rng();
@@ -279,4 +367,51 @@ mod test {
             )
         }
     }
+
+    #[test]
+    fn test_to_sponge_field_elements() -> Result<(), SynthesisError> {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let max_size = ((Fr::MODULUS_BIT_SIZE - 1) / 8) as usize;
+        for len in [0, 1, max_size, max_size + 1, 3 * max_size + 5] {
+            let bytes = (0..len).map(|_| rng.gen()).collect::<Vec<u8>>();
+            let byte_vars = Vec::<UInt8<Fr>>::new_witness(cs.clone(), || Ok(bytes.clone()))?;
+            let elements = byte_vars.to_sponge_field_elements()?;
+            // Native absorption of `[u8]` prefixes the bytes with their
+            // length, as in `Absorb::batch_to_sponge_field_elements`.
+            let mut native = (len as u64).to_le_bytes().to_vec();
+            native.extend_from_slice(&bytes);
+            let expected: Vec<Fr> = native.to_field_elements().unwrap();
+            assert_eq!(elements.len(), expected.len());
+            assert_eq!(elements.value()?, expected);
+        }
+
+        // Trailing zero bytes change the absorbed elements.
+        let short = Vec::<UInt8<Fr>>::new_witness(cs.clone(), || Ok(vec![7u8]))?;
+        let padded = Vec::<UInt8<Fr>>::new_witness(cs.clone(), || Ok(vec![7u8, 0]))?;
+        assert_ne!(
+            short.to_sponge_field_elements()?.value()?,
+            padded.to_sponge_field_elements()?.value()?
+        );
+
+        let byte = UInt8::new_witness(cs.clone(), || Ok(200u8))?;
+        assert_eq!(
+            byte.to_sponge_field_elements()?.value()?,
+            vec![Fr::from(200u8)]
+        );
+
+        let bit = Boolean::new_witness(cs.clone(), || Ok(true))?;
+        assert_eq!(
+            bit.to_sponge_field_elements()?.value()?,
+            vec![Fr::from(1u8)]
+        );
+
+        let element = FpVar::new_witness(cs.clone(), || Ok(Fr::from(7u8)))?;
+        assert_eq!(
+            element.to_sponge_field_elements()?.value()?,
+            vec![Fr::from(7u8)]
+        );
+        assert!(cs.is_satisfied()?);
+        Ok(())
+    }
 }